@@ -1,17 +1,55 @@
-use crate::error::AlgoExecuteError;
-use ink::storage::Mapping;
+use foresta_types::{
+    metadata::{
+        MAX_TOKEN_ATTRIBUTES,
+        METADATA_ATTRIBUTE_KEYS,
+        NAME_KEY,
+        SYMBOL_KEY,
+    },
+    multisig::{
+        AdminAction,
+        Multisig,
+        Proposal,
+        ProposalEvent,
+        SignerSet,
+    },
+    roles::{
+        ADMIN,
+        CURATOR,
+        EXECUTOR,
+        GOVERNANCE,
+        MINTER,
+        TREASURER,
+    },
+    AlgorithmInfo,
+    AlgorithmMetadata,
+    Cid,
+    ForestaError,
+    Operation,
+    PausedOperations,
+};
+use ink::{
+    prelude::string::ToString,
+    storage::Mapping,
+};
 use openbrush::{
     contracts::{
-        ownable::*,
-        psp34::Id,
+        access_control::*,
+        psp34::{
+            Id,
+            PSP34Error,
+        },
     },
+    modifiers,
     traits::{
         Storage,
         String,
     },
 };
 
-#[openbrush::implementation(PSP34)]
+/// Most EA-NFT ids returned by one `get_execute_nfts` call.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+#[openbrush::implementation(PSP34, PSP34Enumerable, PSP34Metadata, AccessControl)]
 #[openbrush::contract]
 pub mod algo_nft {
     use super::*;
@@ -21,106 +59,1065 @@ pub mod algo_nft {
     pub struct AlgorithmNFT {
         #[storage_field]
         psp34: psp34::Data,
-        ownable: ownable::Data,
-        metadata: Mapping<Id, String>,
-        algorithm_cid: Mapping<Id, String>,
+        #[storage_field]
+        access: access_control::Data,
+        #[storage_field]
+        metadata: metadata::Data,
+        #[storage_field]
+        enumerable: enumerable::Data,
+        algorithm_metadata: Mapping<Id, AlgorithmMetadata>,
+        algorithm_cid: Mapping<Id, Cid>,
+        algorithm_version: Mapping<Id, u32>,
         algo_id: u64,
-        execute_nfts: Mapping<Id, Vec<Id>>,
+        execute_nft_count: Mapping<Id, u32>,
+        execute_nfts: Mapping<(Id, u32), Id>,
+        execute_nft_index: Mapping<Id, (Id, u32)>,
+        burned_algorithms: Mapping<Id, ()>,
+        token_attribute_keys: Mapping<Id, Vec<String>>,
+        execute_nft_contract: Option<AccountId>,
+        admins: Vec<AccountId>,
+        signer_set: Option<SignerSet>,
+        proposals: Mapping<u32, Proposal<AdminAction>>,
+        next_proposal_id: u32,
+        paused: PausedOperations,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        operation: Operation,
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        operation: Operation,
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        expires_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        proposal_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCancelled {
+        #[ink(topic)]
+        proposal_id: u32,
+    }
+
+    // Transfers between accounts can be paused; mints are checked in `mint`.
+    #[overrider(psp34::Internal)]
+    fn _before_token_transfer(
+        &mut self,
+        from: Option<&AccountId>,
+        to: Option<&AccountId>,
+        _id: &Id,
+    ) -> Result<(), PSP34Error> {
+        if from.is_some() && to.is_some() && self.paused.is_paused(Operation::Transfer) {
+            return Err(PSP34Error::Custom(String::from("TransferPaused")));
+        }
+        Ok(())
+    }
+
+    // ADMIN holders are tracked so `configure_multisig` can revoke every single-key grant.
+    #[overrider(access_control::Internal)]
+    fn _emit_role_granted(
+        &mut self,
+        role: RoleType,
+        grantee: Option<AccountId>,
+        _grantor: Option<AccountId>,
+    ) {
+        if let (ADMIN, Some(account)) = (role, grantee) {
+            if !self.admins.contains(&account) {
+                self.admins.push(account);
+            }
+        }
+    }
+
+    #[overrider(access_control::Internal)]
+    fn _emit_role_revoked(
+        &mut self,
+        role: RoleType,
+        account: Option<AccountId>,
+        _sender: AccountId,
+    ) {
+        if let (ADMIN, Some(account)) = (role, account) {
+            self.admins.retain(|admin| *admin != account);
+        }
     }
 
     impl AlgorithmNFT {
         #[ink(constructor)]
         pub fn new() -> Self {
-            Self {
+            let mut instance = Self {
                 psp34: Default::default(),
-                ownable: Default::default(),
+                access: Default::default(),
                 metadata: Default::default(),
+                enumerable: Default::default(),
+                algorithm_metadata: Mapping::default(),
                 algorithm_cid: Mapping::default(),
+                algorithm_version: Mapping::default(),
                 algo_id: Default::default(),
+                execute_nft_count: Mapping::default(),
                 execute_nfts: Mapping::default(),
-            }
+                execute_nft_index: Mapping::default(),
+                burned_algorithms: Mapping::default(),
+                token_attribute_keys: Mapping::default(),
+                execute_nft_contract: None,
+                admins: Vec::new(),
+                signer_set: None,
+                proposals: Mapping::default(),
+                next_proposal_id: 0,
+                paused: PausedOperations::default(),
+            };
+            let caller = instance.env().caller();
+            access_control::Internal::_init_with_admin(&mut instance, Some(caller));
+            let _ = AccessControl::grant_role(&mut instance, MINTER, Some(caller));
+            let _ = AccessControl::grant_role(&mut instance, CURATOR, Some(caller));
+            let collection_id = psp34::PSP34Impl::collection_id(&instance);
+            metadata::Internal::_set_attribute(
+                &mut instance,
+                collection_id.clone(),
+                String::from(NAME_KEY),
+                String::from("Foresta Algorithm"),
+            );
+            metadata::Internal::_set_attribute(
+                &mut instance,
+                collection_id,
+                String::from(SYMBOL_KEY),
+                String::from("FALGO"),
+            );
+            instance
         }
 
         #[ink(message)]
+        #[modifiers(only_role(MINTER))]
         pub fn mint(
             &mut self,
             to: AccountId,
             algorithm_cid: String,
-        ) -> Result<Id, AlgoExecuteError> {
+            metadata: AlgorithmMetadata,
+        ) -> Result<Id, ForestaError> {
+            self.ensure_not_paused(Operation::Mint)?;
+            let algorithm_cid = Cid::parse(&algorithm_cid)?;
+            metadata.validate()?;
             let mint_id = self.algo_id.saturating_add(1);
             self.algo_id = mint_id;
+            self.algorithm_metadata.insert(Id::U64(mint_id), &metadata);
             self.algorithm_cid.insert(Id::U64(mint_id), &algorithm_cid);
+            self.algorithm_version.insert(Id::U64(mint_id), &1u32);
             let _ = psp34::Internal::_mint_to(self, to, Id::U64(mint_id));
+            self.write_metadata_attributes(&Id::U64(mint_id), &metadata);
             Ok(Id::U64(mint_id))
         }
 
         #[ink(message)]
+        #[modifiers(only_role(CURATOR))]
         pub fn add_execute_nft(
             &mut self,
             algo_id: Id,
             exec_id: Id,
-        ) -> Result<(), AlgoExecuteError> {
-            self.ensure_owner()?;
-            let mut exec_nfts = self.execute_nfts.get(&algo_id).unwrap_or_default();
-            exec_nfts.push(exec_id);
-            self.execute_nfts.insert(&algo_id, &exec_nfts);
+        ) -> Result<(), ForestaError> {
+            self.ensure_algorithm_exists(&algo_id)?;
+            if self.execute_nft_index.contains(&exec_id) {
+                return Err(ForestaError::ExecuteNftAlreadyLinked);
+            }
+            let index = self.execute_nft_count.get(&algo_id).unwrap_or_default();
+            self.execute_nfts.insert((&algo_id, index), &exec_id);
+            self.execute_nft_index.insert(&exec_id, &(algo_id.clone(), index));
+            self.execute_nft_count.insert(&algo_id, &index.saturating_add(1));
+            Ok(())
+        }
+
+        /// Unlinks an EA-NFT from its AS-NFT. The last EA-NFT of the AS-NFT takes the freed
+        /// index, so the order returned by `get_execute_nfts` can change. Open to `CURATOR` and
+        /// to the EA-NFT contract set with `set_execute_nft_contract`.
+        #[ink(message)]
+        pub fn remove_execute_nft(&mut self, exec_id: Id) -> Result<(), ForestaError> {
+            let caller = self.env().caller();
+            if self.execute_nft_contract != Some(caller)
+                && !AccessControl::has_role(self, CURATOR, Some(caller))
+            {
+                return Err(ForestaError::NotAuthorized);
+            }
+            let (algo_id, index) = self
+                .execute_nft_index
+                .take(&exec_id)
+                .ok_or(ForestaError::ExecuteNftNotLinked)?;
+            let last = self
+                .execute_nft_count
+                .get(&algo_id)
+                .unwrap_or_default()
+                .saturating_sub(1);
+            if index != last {
+                if let Some(moved) = self.execute_nfts.get((&algo_id, last)) {
+                    self.execute_nfts.insert((&algo_id, index), &moved);
+                    self.execute_nft_index.insert(&moved, &(algo_id.clone(), index));
+                }
+            }
+            self.execute_nfts.remove((&algo_id, last));
+            self.execute_nft_count.insert(&algo_id, &last);
             Ok(())
         }
 
+        /// EA-NFT contract allowed to unlink the EA-NFTs it burns. Once a signer set is
+        /// configured this needs a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn set_execute_nft_contract(
+            &mut self,
+            contract: AccountId,
+        ) -> Result<(), ForestaError> {
+            self.direct_admin_action(AdminAction::SetExecuteNftContract(contract))
+        }
+
+        #[ink(message)]
+        pub fn get_execute_nft_contract(&self) -> Option<AccountId> {
+            self.execute_nft_contract
+        }
+
+        /// Up to `limit` EA-NFTs of an AS-NFT starting at `offset`, capped at `MAX_PAGE_SIZE`.
+        #[ink(message)]
+        pub fn get_execute_nfts(&self, algo_id: Id, offset: u32, limit: u32) -> Vec<Id> {
+            let count = self.execute_nft_count.get(&algo_id).unwrap_or_default();
+            let end = offset
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(count);
+            (offset..end)
+                .filter_map(|index| self.execute_nfts.get((&algo_id, index)))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn get_execute_nft_count(&self, algo_id: Id) -> u32 {
+            self.execute_nft_count.get(&algo_id).unwrap_or_default()
+        }
+
+        /// AS-NFT an EA-NFT is linked to.
+        #[ink(message)]
+        pub fn get_algorithm_of(&self, exec_id: Id) -> Option<Id> {
+            self.execute_nft_index
+                .get(&exec_id)
+                .map(|(algo_id, _)| algo_id)
+        }
+
+        /// Sets a collection attribute such as `name`, `symbol` or `baseURI`, read back with
+        /// `PSP34Metadata::get_attribute` on the collection id. Once a signer set is configured
+        /// this needs a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn set_collection_attribute(
+            &mut self,
+            key: String,
+            value: String,
+        ) -> Result<(), ForestaError> {
+            self.direct_admin_action(AdminAction::SetCollectionAttribute(key, value))
+        }
+
+        /// Sets an attribute of a minted AS-NFT, read back with `PSP34Metadata::get_attribute`.
+        /// The attributes mirroring the algorithm metadata only change with
+        /// `set_algorithm_metadata`.
         #[ink(message)]
-        pub fn get_execute_nfts(&self, algo_id: Id) -> Vec<Id> {
-            self.execute_nfts.get(&algo_id).unwrap_or_default()
+        #[modifiers(only_role(CURATOR))]
+        pub fn set_token_attribute(
+            &mut self,
+            algo_id: Id,
+            key: String,
+            value: String,
+        ) -> Result<(), ForestaError> {
+            self.ensure_algorithm_exists(&algo_id)?;
+            if METADATA_ATTRIBUTE_KEYS.contains(&key.as_str()) {
+                return Err(ForestaError::InvalidMetadata);
+            }
+            self.track_attribute_key(&algo_id, &key)?;
+            metadata::Internal::_set_attribute(self, algo_id, key, value);
+            Ok(())
         }
 
         #[ink(message)]
-        pub fn set_metadata(
+        #[modifiers(only_role(CURATOR))]
+        pub fn set_algorithm_metadata(
             &mut self,
             algo_id: Id,
-            metadata: String,
-        ) -> Result<(), AlgoExecuteError> {
-            self.ensure_owner()?;
-            self.metadata.insert(&algo_id, &metadata);
+            metadata: AlgorithmMetadata,
+        ) -> Result<(), ForestaError> {
+            self.ensure_algorithm_exists(&algo_id)?;
+            metadata.validate()?;
+            self.algorithm_metadata.insert(&algo_id, &metadata);
+            self.write_metadata_attributes(&algo_id, &metadata);
             Ok(())
         }
 
         #[ink(message)]
+        pub fn get_algorithm_metadata(&self, algo_id: Id) -> Option<AlgorithmMetadata> {
+            self.algorithm_metadata.get(&algo_id)
+        }
+
+        #[ink(message)]
+        #[modifiers(only_role(CURATOR))]
         pub fn set_algorithm_cid(
             &mut self,
             algo_id: Id,
             algorithm_cid: String,
-        ) -> Result<(), AlgoExecuteError> {
-            self.ensure_owner()?;
+        ) -> Result<(), ForestaError> {
+            self.ensure_not_paused(Operation::CidUpdate)?;
+            self.ensure_algorithm_exists(&algo_id)?;
+            let algorithm_cid = Cid::parse(&algorithm_cid)?;
             self.algorithm_cid.insert(&algo_id, &algorithm_cid);
+            let version = self.algorithm_version.get(&algo_id).unwrap_or_default();
+            self.algorithm_version.insert(&algo_id, &version.saturating_add(1));
             Ok(())
         }
 
         #[ink(message)]
-        pub fn get_metadata(&self, algo_id: Id) -> Option<String> {
-            self.metadata.get(&algo_id)
+        pub fn get_algorithm_cid(&self, algo_id: Id) -> Option<String> {
+            self.algorithm_cid.get(&algo_id).map(|cid| cid.to_string())
         }
 
         #[ink(message)]
-        pub fn get_algorithm_cid(&self, algo_id: Id) -> Option<String> {
-            self.algorithm_cid.get(&algo_id)
+        pub fn get_algorithm_version(&self, algo_id: Id) -> Option<u32> {
+            self.algorithm_version.get(&algo_id)
+        }
+
+        /// Burns an AS-NFT together with its CID, metadata and token attributes. Fails with
+        /// `ExecuteNftsActive` while EA-NFTs are still linked to it, since their holders keep
+        /// their entitlement until they burn them.
+        #[ink(message)]
+        pub fn burn(&mut self, algo_id: Id) -> Result<(), ForestaError> {
+            let owner = psp34::Internal::_owner_of(self, &algo_id)
+                .ok_or(ForestaError::TokenNotFound)?;
+            if owner != self.env().caller() {
+                return Err(ForestaError::NotAuthorized);
+            }
+            if self.execute_nft_count.get(&algo_id).unwrap_or_default() > 0 {
+                return Err(ForestaError::ExecuteNftsActive);
+            }
+            psp34::Internal::_burn_from(self, owner, algo_id.clone())
+                .map_err(|_| ForestaError::TokenNotFound)?;
+            self.algorithm_cid.remove(&algo_id);
+            self.algorithm_version.remove(&algo_id);
+            self.algorithm_metadata.remove(&algo_id);
+            self.execute_nft_count.remove(&algo_id);
+            self.clear_token_attributes(&algo_id);
+            self.burned_algorithms.insert(&algo_id, &());
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_burned(&self, algo_id: Id) -> bool {
+            self.burned_algorithms.contains(&algo_id)
+        }
+
+        /// Halts an operation until it is unpaused. Once a signer set is configured this needs a
+        /// proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn pause(&mut self, operation: Operation) -> Result<(), ForestaError> {
+            self.direct_admin_action(AdminAction::Pause(operation))
+        }
+
+        /// Resumes a paused operation. Once a signer set is configured this needs a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn unpause(&mut self, operation: Operation) -> Result<(), ForestaError> {
+            self.direct_admin_action(AdminAction::Unpause(operation))
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self, operation: Operation) -> bool {
+            self.paused.is_paused(operation)
+        }
+
+        fn halt(&mut self, operation: Operation) {
+            self.paused.pause(operation);
+            self.env().emit_event(Paused {
+                operation,
+                account: self.env().caller(),
+            });
+        }
+
+        fn resume(&mut self, operation: Operation) {
+            self.paused.unpause(operation);
+            self.env().emit_event(Unpaused {
+                operation,
+                account: self.env().caller(),
+            });
+        }
+
+        // Mirrors the short metadata fields into PSP34Metadata attributes.
+        fn write_metadata_attributes(&mut self, algo_id: &Id, metadata: &AlgorithmMetadata) {
+            for (key, value) in metadata.attributes() {
+                match value {
+                    Some(value) => {
+                        metadata::Internal::_set_attribute(
+                            self,
+                            algo_id.clone(),
+                            String::from(key),
+                            value,
+                        );
+                    }
+                    None => {
+                        self.metadata
+                            .attributes
+                            .remove(&(algo_id.clone(), String::from(key)));
+                    }
+                }
+            }
+        }
+
+        // Custom attribute keys are remembered so `burn` can clear them.
+        fn track_attribute_key(&mut self, algo_id: &Id, key: &String) -> Result<(), ForestaError> {
+            let mut keys = self.token_attribute_keys.get(algo_id).unwrap_or_default();
+            if keys.contains(key) {
+                return Ok(());
+            }
+            if keys.len() >= MAX_TOKEN_ATTRIBUTES {
+                return Err(ForestaError::TooManyAttributes);
+            }
+            keys.push(key.clone());
+            self.token_attribute_keys.insert(algo_id, &keys);
+            Ok(())
+        }
+
+        fn clear_token_attributes(&mut self, algo_id: &Id) {
+            let custom_keys = self.token_attribute_keys.take(algo_id).unwrap_or_default();
+            let keys = METADATA_ATTRIBUTE_KEYS
+                .iter()
+                .map(|key| String::from(*key))
+                .chain(custom_keys);
+            for key in keys {
+                self.metadata.attributes.remove(&(algo_id.clone(), key));
+            }
+        }
+
+        fn ensure_algorithm_exists(&self, algo_id: &Id) -> Result<(), ForestaError> {
+            if self.burned_algorithms.contains(algo_id) {
+                return Err(ForestaError::AlgorithmBurned);
+            }
+            if psp34::Internal::_owner_of(self, algo_id).is_none() {
+                return Err(ForestaError::TokenNotFound);
+            }
+            Ok(())
+        }
+
+        fn ensure_not_paused(&self, operation: Operation) -> Result<(), ForestaError> {
+            if self.paused.is_paused(operation) {
+                return Err(ForestaError::OperationPaused);
+            }
+            Ok(())
+        }
+
+        /// Hands role administration over to a k-of-n signer set and revokes ADMIN from every
+        /// holder. From then on roles and admin settings only change through approved proposals.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn configure_multisig(&mut self, signer_set: SignerSet) -> Result<(), ForestaError> {
+            if self.signer_set.is_some() {
+                return Err(ForestaError::MultisigRequired);
+            }
+            signer_set.validate()?;
+            // GOVERNANCE administers itself, so no account can grant it outside a proposal.
+            for role in [ADMIN, MINTER, CURATOR, EXECUTOR, TREASURER, GOVERNANCE] {
+                access_control::Internal::_set_role_admin(self, role, GOVERNANCE);
+            }
+            for admin in core::mem::take(&mut self.admins) {
+                access_control::Internal::_do_revoke_role(self, ADMIN, Some(admin));
+            }
+            self.signer_set = Some(signer_set);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_signer_set(&self) -> Option<SignerSet> {
+            self.signer_set.clone()
+        }
+
+        #[ink(message)]
+        pub fn propose(&mut self, action: AdminAction, ttl_ms: u64) -> Result<u32, ForestaError> {
+            self.submit_proposal(action, ttl_ms)
         }
 
-        fn ensure_owner(&mut self) -> Result<(), AlgoExecuteError> {
-            let owner = self
-                .ownable
-                .owner
-                .get()
-                .ok_or(AlgoExecuteError::NotAuthorized)?;
-            if Some(owner) == Some(Some(self.env().caller())) {
-                Ok(())
-            } else {
-                Err(AlgoExecuteError::NotAuthorized)
+        #[ink(message)]
+        pub fn approve_proposal(&mut self, proposal_id: u32) -> Result<(), ForestaError> {
+            self.add_approval(proposal_id)
+        }
+
+        /// Withdraws a proposal. Only its proposer can cancel it.
+        #[ink(message)]
+        pub fn cancel_proposal(&mut self, proposal_id: u32) -> Result<(), ForestaError> {
+            self.withdraw_proposal(proposal_id)
+        }
+
+        #[ink(message)]
+        pub fn get_proposal(&self, proposal_id: u32) -> Option<Proposal<AdminAction>> {
+            self.proposals.get(proposal_id)
+        }
+
+        // Single-key path for admin actions, only open while no signer set is configured.
+        fn direct_admin_action(&mut self, action: AdminAction) -> Result<(), ForestaError> {
+            if self.signer_set.is_some() {
+                return Err(ForestaError::MultisigRequired);
             }
+            self.validate_action(&action)?;
+            self.apply_action(action);
+            Ok(())
         }
 
-        pub fn fetch_algorithm_data(&self, algo_id: Id) -> Result<String, AlgoExecuteError> {
-            self.algorithm_cid
+        /// CID and version of an AS-NFT in one call, failing with `AlgorithmCidNotFound` for
+        /// unknown ids and `AlgorithmBurned` for burned ones.
+        #[ink(message)]
+        pub fn get_algorithm_info(&self, algo_id: Id) -> Result<AlgorithmInfo, ForestaError> {
+            if self.burned_algorithms.contains(&algo_id) {
+                return Err(ForestaError::AlgorithmBurned);
+            }
+            let cid = self
+                .algorithm_cid
                 .get(&algo_id)
-                .ok_or(AlgoExecuteError::DataNotFound)
+                .ok_or(ForestaError::AlgorithmCidNotFound)?;
+            let version = self.algorithm_version.get(&algo_id).unwrap_or_default();
+            Ok(AlgorithmInfo {
+                cid: cid.to_string(),
+                version,
+            })
+        }
+    }
+
+    impl Multisig for AlgorithmNFT {
+        type Action = AdminAction;
+        type Error = ForestaError;
+
+        fn signer_set(&self) -> Option<SignerSet> {
+            self.signer_set.clone()
+        }
+
+        fn load_proposal(&self, proposal_id: u32) -> Option<Proposal<AdminAction>> {
+            self.proposals.get(proposal_id)
+        }
+
+        fn store_proposal(&mut self, proposal_id: u32, proposal: &Proposal<AdminAction>) {
+            self.proposals.insert(proposal_id, proposal);
+        }
+
+        fn remove_proposal(&mut self, proposal_id: u32) {
+            self.proposals.remove(proposal_id);
+        }
+
+        fn take_proposal_id(&mut self) -> u32 {
+            let proposal_id = self.next_proposal_id;
+            self.next_proposal_id = proposal_id.saturating_add(1);
+            proposal_id
+        }
+
+        fn caller(&self) -> AccountId {
+            self.env().caller()
+        }
+
+        fn now(&self) -> u64 {
+            self.env().block_timestamp()
+        }
+
+        fn validate_action(&self, action: &AdminAction) -> Result<(), ForestaError> {
+            if let AdminAction::SetAlgoNftContract(_) = action {
+                return Err(ForestaError::UnsupportedAction);
+            }
+            action.validate()
+        }
+
+        fn apply_action(&mut self, action: AdminAction) {
+            match action {
+                AdminAction::GrantRole(role, account) => {
+                    access_control::Internal::_setup_role(self, role, Some(account));
+                }
+                AdminAction::RevokeRole(role, account) => {
+                    access_control::Internal::_do_revoke_role(self, role, Some(account));
+                }
+                AdminAction::SetSignerSet(signer_set) => self.signer_set = Some(signer_set),
+                AdminAction::Unpause(operation) => self.resume(operation),
+                AdminAction::Pause(operation) => self.halt(operation),
+                AdminAction::SetCollectionAttribute(key, value) => {
+                    let collection_id = psp34::PSP34Impl::collection_id(self);
+                    metadata::Internal::_set_attribute(self, collection_id, key, value);
+                }
+                AdminAction::SetExecuteNftContract(contract) => {
+                    self.execute_nft_contract = Some(contract);
+                }
+                // Refused by `validate_action`.
+                AdminAction::SetAlgoNftContract(_) => (),
+            }
+        }
+
+        fn emit_proposal_event(&self, proposal_id: u32, event: ProposalEvent) {
+            match event {
+                ProposalEvent::Created {
+                    proposer,
+                    expires_at,
+                } => {
+                    self.env().emit_event(ProposalCreated {
+                        proposal_id,
+                        proposer,
+                        expires_at,
+                    })
+                }
+                ProposalEvent::Executed => self.env().emit_event(ProposalExecuted { proposal_id }),
+                ProposalEvent::Cancelled => {
+                    self.env().emit_event(ProposalCancelled { proposal_id })
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use foresta_types::metadata::{
+            AUTHORS_KEY,
+            DOI_KEY,
+            TITLE_KEY,
+        };
+        use ink::{
+            env::{
+                test,
+                DefaultEnvironment,
+            },
+            prelude::format,
+        };
+
+        const TEST_CID: &str = "QmY7Yh4UquoXHLPFo2XbhXkhBvFoPwmQUSa92pxnxjQuPU";
+
+        fn accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        fn test_metadata() -> AlgorithmMetadata {
+            AlgorithmMetadata {
+                title: String::from("Forest cover classifier"),
+                authors: ink::prelude::vec![String::from("Ada Lovelace")],
+                license: String::from("Apache-2.0"),
+                runtime: String::from("python-3.11"),
+                entrypoint: String::from("main.py"),
+                input_schema: None,
+                output_schema: None,
+                doi: Some(String::from("10.5281/zenodo.1234567")),
+                tags: Vec::new(),
+                content_type: String::from("text/x-python"),
+                extensions: None,
+            }
+        }
+
+        fn mint(contract: &mut AlgorithmNFT, to: AccountId) -> Id {
+            contract
+                .mint(to, String::from(TEST_CID), test_metadata())
+                .unwrap()
         }
-    }    
+
+        fn attribute(contract: &AlgorithmNFT, id: Id, key: &str) -> Option<String> {
+            PSP34Metadata::get_attribute(contract, id, String::from(key))
+        }
+
+        // PSP34 ENUMERABLE TESTS
+        #[ink::test]
+        fn enumerable_tracks_mints_transfers_and_burns() {
+            let accounts = accounts();
+            let mut contract = AlgorithmNFT::new();
+            let first = mint(&mut contract, accounts.alice);
+            let second = mint(&mut contract, accounts.alice);
+            let third = mint(&mut contract, accounts.bob);
+
+            assert_eq!(PSP34::total_supply(&contract), 3);
+            assert_eq!(
+                PSP34Enumerable::token_by_index(&contract, 2),
+                Ok(third.clone())
+            );
+            assert_eq!(
+                PSP34Enumerable::owners_token_by_index(&contract, accounts.alice, 1),
+                Ok(second.clone())
+            );
+
+            assert!(
+                PSP34::transfer(&mut contract, accounts.bob, first.clone(), Vec::new()).is_ok()
+            );
+            assert_eq!(PSP34::balance_of(&contract, accounts.alice), 1);
+            assert_eq!(
+                PSP34Enumerable::owners_token_by_index(&contract, accounts.alice, 0),
+                Ok(second.clone())
+            );
+            assert_eq!(
+                PSP34Enumerable::owners_token_by_index(&contract, accounts.bob, 1),
+                Ok(first)
+            );
+
+            assert!(contract.burn(second).is_ok());
+            assert_eq!(PSP34::total_supply(&contract), 2);
+            assert!(PSP34Enumerable::owners_token_by_index(&contract, accounts.alice, 0).is_err());
+            assert!(PSP34Enumerable::token_by_index(&contract, 2).is_err());
+        }
+
+        // EXECUTE NFT INDEX TESTS
+        fn link(contract: &mut AlgorithmNFT, algo_id: &Id, count: u64) -> Vec<Id> {
+            (1..=count)
+                .map(|exec_id| {
+                    assert!(contract
+                        .add_execute_nft(algo_id.clone(), Id::U64(exec_id))
+                        .is_ok());
+                    Id::U64(exec_id)
+                })
+                .collect()
+        }
+
+        #[ink::test]
+        fn removing_from_the_middle_moves_the_last_entry() {
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts().alice);
+            let exec_ids = link(&mut contract, &algo_id, 4);
+
+            assert!(contract.remove_execute_nft(exec_ids[1].clone()).is_ok());
+            assert_eq!(contract.get_execute_nft_count(algo_id.clone()), 3);
+            assert_eq!(
+                contract.get_execute_nfts(algo_id.clone(), 0, 10),
+                ink::prelude::vec![
+                    exec_ids[0].clone(),
+                    exec_ids[3].clone(),
+                    exec_ids[2].clone()
+                ]
+            );
+            assert_eq!(contract.get_algorithm_of(exec_ids[1].clone()), None);
+            assert_eq!(
+                contract.get_algorithm_of(exec_ids[3].clone()),
+                Some(algo_id.clone())
+            );
+
+            // The moved entry is removed from its new index.
+            assert!(contract.remove_execute_nft(exec_ids[3].clone()).is_ok());
+            assert_eq!(
+                contract.get_execute_nfts(algo_id, 0, 10),
+                ink::prelude::vec![exec_ids[0].clone(), exec_ids[2].clone()]
+            );
+        }
+
+        #[ink::test]
+        fn removing_the_last_entry_shrinks_the_list() {
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts().alice);
+            let exec_ids = link(&mut contract, &algo_id, 2);
+
+            assert!(contract.remove_execute_nft(exec_ids[1].clone()).is_ok());
+            assert_eq!(
+                contract.get_execute_nfts(algo_id.clone(), 0, 10),
+                ink::prelude::vec![exec_ids[0].clone()]
+            );
+            assert!(contract.remove_execute_nft(exec_ids[0].clone()).is_ok());
+            assert_eq!(contract.get_execute_nft_count(algo_id.clone()), 0);
+            assert!(contract.get_execute_nfts(algo_id, 0, 10).is_empty());
+            assert_eq!(
+                contract.remove_execute_nft(exec_ids[0].clone()),
+                Err(ForestaError::ExecuteNftNotLinked)
+            );
+        }
+
+        #[ink::test]
+        fn pages_are_bounded_by_count_and_page_size() {
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts().alice);
+            let exec_ids = link(&mut contract, &algo_id, u64::from(MAX_PAGE_SIZE) + 5);
+
+            assert_eq!(
+                contract.get_execute_nfts(algo_id.clone(), 3, 2),
+                exec_ids[3..5].to_vec()
+            );
+            assert_eq!(
+                contract.get_execute_nfts(algo_id.clone(), MAX_PAGE_SIZE, 10),
+                exec_ids[MAX_PAGE_SIZE as usize..].to_vec()
+            );
+            assert_eq!(
+                contract.get_execute_nfts(algo_id.clone(), 0, u32::MAX),
+                exec_ids[..MAX_PAGE_SIZE as usize].to_vec()
+            );
+            assert!(contract
+                .get_execute_nfts(algo_id.clone(), MAX_PAGE_SIZE + 5, 10)
+                .is_empty());
+            assert!(contract
+                .get_execute_nfts(algo_id, u32::MAX, u32::MAX)
+                .is_empty());
+        }
+
+        // BURN TESTS
+        #[ink::test]
+        fn burned_algorithms_are_rejected() {
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts().alice);
+            assert!(contract.get_algorithm_info(algo_id.clone()).is_ok());
+
+            assert!(contract.burn(algo_id.clone()).is_ok());
+            assert!(contract.is_burned(algo_id.clone()));
+            assert_eq!(
+                contract.get_algorithm_info(algo_id.clone()),
+                Err(ForestaError::AlgorithmBurned)
+            );
+            assert_eq!(
+                contract.set_algorithm_cid(algo_id.clone(), String::from(TEST_CID)),
+                Err(ForestaError::AlgorithmBurned)
+            );
+            assert_eq!(
+                contract.add_execute_nft(algo_id.clone(), Id::U64(1)),
+                Err(ForestaError::AlgorithmBurned)
+            );
+            assert_eq!(contract.get_algorithm_cid(algo_id.clone()), None);
+            assert_eq!(contract.get_algorithm_metadata(algo_id), None);
+            assert_eq!(
+                contract.get_algorithm_info(Id::U64(9)),
+                Err(ForestaError::AlgorithmCidNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn burn_clears_token_attributes() {
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts().alice);
+            assert!(contract
+                .set_token_attribute(
+                    algo_id.clone(),
+                    String::from("image"),
+                    String::from("ipfs://x")
+                )
+                .is_ok());
+
+            assert!(contract.burn(algo_id.clone()).is_ok());
+            assert_eq!(attribute(&contract, algo_id.clone(), TITLE_KEY), None);
+            assert_eq!(attribute(&contract, algo_id.clone(), AUTHORS_KEY), None);
+            assert_eq!(attribute(&contract, algo_id, "image"), None);
+        }
+
+        #[ink::test]
+        fn custom_token_attributes_are_bounded() {
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts().alice);
+            for index in 0..MAX_TOKEN_ATTRIBUTES {
+                assert!(contract
+                    .set_token_attribute(algo_id.clone(), format!("key{}", index), String::new())
+                    .is_ok());
+            }
+            // Overwriting a known key doesn't count against the limit.
+            assert!(contract
+                .set_token_attribute(algo_id.clone(), String::from("key0"), String::from("x"))
+                .is_ok());
+            assert_eq!(
+                contract.set_token_attribute(algo_id, String::from("one more"), String::new()),
+                Err(ForestaError::TooManyAttributes)
+            );
+        }
+
+        #[ink::test]
+        fn linked_execute_nfts_block_burn() {
+            let accounts = accounts();
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts.alice);
+            let exec_ids = link(&mut contract, &algo_id, 2);
+            assert!(contract.set_execute_nft_contract(accounts.django).is_ok());
+            assert_eq!(
+                contract.burn(algo_id.clone()),
+                Err(ForestaError::ExecuteNftsActive)
+            );
+
+            // The EA-NFT contract unlinks them as their holders burn them.
+            set_caller(accounts.django);
+            assert!(contract.remove_execute_nft(exec_ids[0].clone()).is_ok());
+            set_caller(accounts.alice);
+            assert_eq!(
+                contract.burn(algo_id.clone()),
+                Err(ForestaError::ExecuteNftsActive)
+            );
+
+            set_caller(accounts.django);
+            assert!(contract.remove_execute_nft(exec_ids[1].clone()).is_ok());
+            set_caller(accounts.alice);
+            assert!(contract.burn(algo_id).is_ok());
+        }
+
+        #[ink::test]
+        fn execute_nft_contract_unlinks_without_curator() {
+            let accounts = accounts();
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts.alice);
+            let exec_ids = link(&mut contract, &algo_id, 2);
+            assert!(contract.set_execute_nft_contract(accounts.django).is_ok());
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.remove_execute_nft(exec_ids[0].clone()),
+                Err(ForestaError::NotAuthorized)
+            );
+            assert_eq!(
+                contract.set_execute_nft_contract(accounts.charlie),
+                Err(ForestaError::NotAuthorized)
+            );
+
+            set_caller(accounts.django);
+            assert!(!AccessControl::has_role(
+                &contract,
+                CURATOR,
+                Some(accounts.django)
+            ));
+            assert!(contract.remove_execute_nft(exec_ids[0].clone()).is_ok());
+            assert_eq!(contract.get_execute_nft_count(algo_id), 1);
+        }
+
+        // MULTISIG TESTS
+        fn multisig_contract() -> AlgorithmNFT {
+            let accounts = accounts();
+            let mut contract = AlgorithmNFT::new();
+            assert!(AccessControl::grant_role(&mut contract, ADMIN, Some(accounts.bob)).is_ok());
+            assert!(contract
+                .configure_multisig(SignerSet {
+                    signers: ink::prelude::vec![accounts.alice, accounts.charlie],
+                    threshold: 2,
+                })
+                .is_ok());
+            contract
+        }
+
+        #[ink::test]
+        fn configure_multisig_revokes_single_key_admins() {
+            let accounts = accounts();
+            let mut contract = multisig_contract();
+            for account in [accounts.alice, accounts.bob] {
+                assert!(!AccessControl::has_role(&contract, ADMIN, Some(account)));
+            }
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                AccessControl::grant_role(&mut contract, GOVERNANCE, Some(accounts.bob)),
+                Err(AccessControlError::MissingRole)
+            );
+            assert_eq!(
+                contract.set_execute_nft_contract(accounts.bob),
+                Err(ForestaError::NotAuthorized)
+            );
+            assert_eq!(
+                contract.pause(Operation::Mint),
+                Err(ForestaError::NotAuthorized)
+            );
+        }
+
+        #[ink::test]
+        fn admin_settings_need_a_proposal_under_multisig() {
+            let accounts = accounts();
+            let mut contract = multisig_contract();
+
+            let proposal_id = contract
+                .propose(AdminAction::SetExecuteNftContract(accounts.django), 60_000)
+                .unwrap();
+            assert_eq!(contract.get_execute_nft_contract(), None);
+            set_caller(accounts.charlie);
+            assert!(contract.approve_proposal(proposal_id).is_ok());
+            assert_eq!(contract.get_execute_nft_contract(), Some(accounts.django));
+
+            assert_eq!(
+                contract.propose(AdminAction::SetAlgoNftContract(accounts.django), 60_000),
+                Err(ForestaError::UnsupportedAction)
+            );
+        }
+
+        // PSP34 METADATA TESTS
+        #[ink::test]
+        fn collection_attributes_are_set_by_admin() {
+            let mut contract = AlgorithmNFT::new();
+            let collection_id = PSP34::collection_id(&contract);
+            assert_eq!(
+                attribute(&contract, collection_id.clone(), NAME_KEY),
+                Some(String::from("Foresta Algorithm"))
+            );
+            assert_eq!(
+                attribute(&contract, collection_id.clone(), SYMBOL_KEY),
+                Some(String::from("FALGO"))
+            );
+
+            set_caller(accounts().bob);
+            assert_eq!(
+                contract.set_collection_attribute(String::from(NAME_KEY), String::from("Other")),
+                Err(ForestaError::NotAuthorized)
+            );
+            assert_eq!(
+                attribute(&contract, collection_id, NAME_KEY),
+                Some(String::from("Foresta Algorithm"))
+            );
+        }
+
+        #[ink::test]
+        fn metadata_is_mirrored_into_token_attributes() {
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts().alice);
+            assert_eq!(
+                attribute(&contract, algo_id.clone(), TITLE_KEY),
+                Some(String::from("Forest cover classifier"))
+            );
+            assert_eq!(
+                attribute(&contract, algo_id.clone(), AUTHORS_KEY),
+                Some(String::from("[\"Ada Lovelace\"]"))
+            );
+
+            let mut metadata = test_metadata();
+            metadata.title = String::from("Forest cover classifier v2");
+            metadata.doi = None;
+            assert!(contract
+                .set_algorithm_metadata(algo_id.clone(), metadata)
+                .is_ok());
+            assert_eq!(
+                attribute(&contract, algo_id.clone(), TITLE_KEY),
+                Some(String::from("Forest cover classifier v2"))
+            );
+            assert_eq!(attribute(&contract, algo_id, DOI_KEY), None);
+        }
+
+        #[ink::test]
+        fn token_attributes_need_curator_and_an_existing_token() {
+            let mut contract = AlgorithmNFT::new();
+            let algo_id = mint(&mut contract, accounts().alice);
+
+            assert!(contract
+                .set_token_attribute(
+                    algo_id.clone(),
+                    String::from("image"),
+                    String::from("ipfs://x")
+                )
+                .is_ok());
+            assert_eq!(
+                attribute(&contract, algo_id.clone(), "image"),
+                Some(String::from("ipfs://x"))
+            );
+            assert_eq!(
+                contract.set_token_attribute(
+                    algo_id.clone(),
+                    String::from(TITLE_KEY),
+                    String::new()
+                ),
+                Err(ForestaError::InvalidMetadata)
+            );
+            assert_eq!(
+                contract.set_token_attribute(Id::U64(7), String::from("image"), String::new()),
+                Err(ForestaError::TokenNotFound)
+            );
+
+            set_caller(accounts().bob);
+            assert_eq!(
+                contract.set_token_attribute(algo_id, String::from("image"), String::new()),
+                Err(ForestaError::NotAuthorized)
+            );
+        }
+    }
 }
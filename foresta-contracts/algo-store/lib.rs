@@ -2,7 +2,6 @@
 #![feature(min_specialization)]
 
 pub mod algo_nft;
-pub mod error;
 
 pub use algo_nft::*;
-pub use error::AlgoExecuteError;
+pub use foresta_types::ForestaError;
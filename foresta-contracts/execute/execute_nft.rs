@@ -1,16 +1,68 @@
 // Handles the execution permissions associated with NFTs. This is critical for ensuring that only authorized users can execute the
 // algorithms associated with specific NFTs.
-use crate::error::AlgoExecuteError;
-use ink::storage::Mapping;
+use foresta_types::{
+    error::fallible_call_result,
+    metadata::{
+        MAX_TOKEN_ATTRIBUTES,
+        NAME_KEY,
+        SYMBOL_KEY,
+    },
+    multisig::{
+        AdminAction,
+        Multisig,
+        Proposal,
+        ProposalEvent,
+        SignerSet,
+    },
+    roles::{
+        ADMIN,
+        CURATOR,
+        EXECUTOR,
+        GOVERNANCE,
+        MINTER,
+        TREASURER,
+    },
+    CallError,
+    ForestaError,
+    Operation,
+    PausedOperations,
+};
+use ink::{
+    env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+    },
+    prelude::string::String,
+    storage::Mapping,
+};
 use openbrush::{
     contracts::{
-        ownable::*,
-        psp34::Id,
+        access_control::*,
+        psp34::{
+            Id,
+            PSP34Error,
+        },
     },
+    modifiers,
     traits::Storage,
 };
 
-#[openbrush::implementation(PSP34)]
+const REMOVE_EXECUTE_NFT: [u8; 4] = ink::selector_bytes!("remove_execute_nft");
+
+// An EA-NFT that was never linked, or that a curator already unlinked, can still be burned.
+fn unlink_result(result: Result<(), CallError>) -> Result<(), ForestaError> {
+    match result {
+        Ok(()) | Err(CallError::Callee(ForestaError::ExecuteNftNotLinked)) => Ok(()),
+        Err(CallError::Callee(err)) => Err(err),
+        Err(_) => Err(ForestaError::CallFailed),
+    }
+}
+
+#[openbrush::implementation(PSP34, PSP34Enumerable, PSP34Metadata, AccessControl)]
 #[openbrush::contract]
 pub mod execute_nft {
     use super::*;
@@ -20,28 +72,216 @@ pub mod execute_nft {
     pub struct ExecutionNFT {
         #[storage_field]
         psp34: psp34::Data,
-        ownable: ownable::Data,
+        #[storage_field]
+        access: access_control::Data,
+        #[storage_field]
+        metadata: metadata::Data,
+        #[storage_field]
+        enumerable: enumerable::Data,
         token_id: u64,
+        algo_nft_contract: Option<AccountId>,
+        token_attribute_keys: Mapping<Id, Vec<String>>,
+        admins: Vec<AccountId>,
+        signer_set: Option<SignerSet>,
+        proposals: Mapping<u32, Proposal<AdminAction>>,
+        next_proposal_id: u32,
+        paused: PausedOperations,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        operation: Operation,
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        operation: Operation,
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        expires_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        proposal_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCancelled {
+        #[ink(topic)]
+        proposal_id: u32,
+    }
+
+    // Transfers between accounts can be paused; mints are checked in `mint`.
+    #[overrider(psp34::Internal)]
+    fn _before_token_transfer(
+        &mut self,
+        from: Option<&AccountId>,
+        to: Option<&AccountId>,
+        _id: &Id,
+    ) -> Result<(), PSP34Error> {
+        if from.is_some() && to.is_some() && self.paused.is_paused(Operation::Transfer) {
+            return Err(PSP34Error::Custom(String::from("TransferPaused")));
+        }
+        Ok(())
+    }
+
+    // ADMIN holders are tracked so `configure_multisig` can revoke every single-key grant.
+    #[overrider(access_control::Internal)]
+    fn _emit_role_granted(
+        &mut self,
+        role: RoleType,
+        grantee: Option<AccountId>,
+        _grantor: Option<AccountId>,
+    ) {
+        if let (ADMIN, Some(account)) = (role, grantee) {
+            if !self.admins.contains(&account) {
+                self.admins.push(account);
+            }
+        }
+    }
+
+    #[overrider(access_control::Internal)]
+    fn _emit_role_revoked(
+        &mut self,
+        role: RoleType,
+        account: Option<AccountId>,
+        _sender: AccountId,
+    ) {
+        if let (ADMIN, Some(account)) = (role, account) {
+            self.admins.retain(|admin| *admin != account);
+        }
     }
 
     impl ExecutionNFT {
         #[ink(constructor)]
         pub fn new() -> Self {
-            Self {
+            let mut instance = Self {
                 psp34: Default::default(),
-                ownable: Default::default(),
+                access: Default::default(),
+                metadata: Default::default(),
+                enumerable: Default::default(),
                 token_id: Default::default(),
-            }
+                algo_nft_contract: None,
+                token_attribute_keys: Mapping::default(),
+                admins: Vec::new(),
+                signer_set: None,
+                proposals: Mapping::default(),
+                next_proposal_id: 0,
+                paused: PausedOperations::default(),
+            };
+            let caller = instance.env().caller();
+            access_control::Internal::_init_with_admin(&mut instance, Some(caller));
+            let _ = AccessControl::grant_role(&mut instance, MINTER, Some(caller));
+            let collection_id = psp34::PSP34Impl::collection_id(&instance);
+            metadata::Internal::_set_attribute(
+                &mut instance,
+                collection_id.clone(),
+                String::from(NAME_KEY),
+                String::from("Foresta Execution"),
+            );
+            metadata::Internal::_set_attribute(
+                &mut instance,
+                collection_id,
+                String::from(SYMBOL_KEY),
+                String::from("FEXEC"),
+            );
+            instance
         }
 
         #[ink(message)]
-        pub fn mint(&mut self, to: AccountId) -> Result<Id, AlgoExecuteError> {
+        #[modifiers(only_role(MINTER))]
+        pub fn mint(&mut self, to: AccountId) -> Result<Id, ForestaError> {
+            self.ensure_not_paused(Operation::Mint)?;
             let mint_id = self.token_id.saturating_add(1);
             self.token_id = mint_id;
             let _ = psp34::Internal::_mint_to(self, to, Id::U64(mint_id));
             Ok(Id::U64(mint_id))
         }
-        
+
+        /// Sets a collection attribute such as `name`, `symbol` or `baseURI`, read back with
+        /// `PSP34Metadata::get_attribute` on the collection id. Once a signer set is configured
+        /// this needs a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn set_collection_attribute(
+            &mut self,
+            key: String,
+            value: String,
+        ) -> Result<(), ForestaError> {
+            self.direct_admin_action(AdminAction::SetCollectionAttribute(key, value))
+        }
+
+        /// Sets an attribute of a minted EA-NFT, read back with `PSP34Metadata::get_attribute`.
+        #[ink(message)]
+        #[modifiers(only_role(MINTER))]
+        pub fn set_token_attribute(
+            &mut self,
+            exec_id: Id,
+            key: String,
+            value: String,
+        ) -> Result<(), ForestaError> {
+            if psp34::Internal::_owner_of(self, &exec_id).is_none() {
+                return Err(ForestaError::TokenNotFound);
+            }
+            self.track_attribute_key(&exec_id, &key)?;
+            metadata::Internal::_set_attribute(self, exec_id, key, value);
+            Ok(())
+        }
+
+        /// AS-NFT contract that EA-NFTs are unlinked from when burned. It has to allow this
+        /// contract with `set_execute_nft_contract`. Once a signer set is configured this needs a
+        /// proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn set_algo_nft_contract(&mut self, contract: AccountId) -> Result<(), ForestaError> {
+            self.direct_admin_action(AdminAction::SetAlgoNftContract(contract))
+        }
+
+        #[ink(message)]
+        pub fn get_algo_nft_contract(&self) -> Option<AccountId> {
+            self.algo_nft_contract
+        }
+
+        /// Burns an EA-NFT and its token attributes after unlinking it from its AS-NFT.
+        #[ink(message)]
+        pub fn burn(&mut self, exec_id: Id) -> Result<(), ForestaError> {
+            let owner = psp34::Internal::_owner_of(self, &exec_id)
+                .ok_or(ForestaError::TokenNotFound)?;
+            if owner != self.env().caller() {
+                return Err(ForestaError::NotAuthorized);
+            }
+            let algo_nft_contract = self
+                .algo_nft_contract
+                .ok_or(ForestaError::AlgorithmNftNotSet)?;
+            let unlinked = fallible_call_result(
+                build_call::<DefaultEnvironment>()
+                    .call(algo_nft_contract)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(REMOVE_EXECUTE_NFT))
+                            .push_arg(exec_id.clone()),
+                    )
+                    .returns::<Result<(), ForestaError>>()
+                    .try_invoke(),
+            );
+            unlink_result(unlinked)?;
+            psp34::Internal::_burn_from(self, owner, exec_id.clone())
+                .map_err(|_| ForestaError::TokenNotFound)?;
+            self.clear_token_attributes(&exec_id);
+            Ok(())
+        }
+
         #[ink(message)]
         pub fn is_owner(&self, exec_id: Id, owner_address: AccountId) -> bool {
             match psp34::Internal::_owner_of(self, &exec_id) {
@@ -49,5 +289,385 @@ pub mod execute_nft {
                 None => false,
             }
         }
+
+        /// Halts an operation until it is unpaused. Once a signer set is configured this needs a
+        /// proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn pause(&mut self, operation: Operation) -> Result<(), ForestaError> {
+            self.direct_admin_action(AdminAction::Pause(operation))
+        }
+
+        /// Resumes a paused operation. Once a signer set is configured this needs a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn unpause(&mut self, operation: Operation) -> Result<(), ForestaError> {
+            self.direct_admin_action(AdminAction::Unpause(operation))
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self, operation: Operation) -> bool {
+            self.paused.is_paused(operation)
+        }
+
+        fn halt(&mut self, operation: Operation) {
+            self.paused.pause(operation);
+            self.env().emit_event(Paused {
+                operation,
+                account: self.env().caller(),
+            });
+        }
+
+        fn resume(&mut self, operation: Operation) {
+            self.paused.unpause(operation);
+            self.env().emit_event(Unpaused {
+                operation,
+                account: self.env().caller(),
+            });
+        }
+
+        // Custom attribute keys are remembered so `burn` can clear them.
+        fn track_attribute_key(&mut self, exec_id: &Id, key: &String) -> Result<(), ForestaError> {
+            let mut keys = self.token_attribute_keys.get(exec_id).unwrap_or_default();
+            if keys.contains(key) {
+                return Ok(());
+            }
+            if keys.len() >= MAX_TOKEN_ATTRIBUTES {
+                return Err(ForestaError::TooManyAttributes);
+            }
+            keys.push(key.clone());
+            self.token_attribute_keys.insert(exec_id, &keys);
+            Ok(())
+        }
+
+        fn clear_token_attributes(&mut self, exec_id: &Id) {
+            for key in self.token_attribute_keys.take(exec_id).unwrap_or_default() {
+                self.metadata.attributes.remove(&(exec_id.clone(), key));
+            }
+        }
+
+        fn ensure_not_paused(&self, operation: Operation) -> Result<(), ForestaError> {
+            if self.paused.is_paused(operation) {
+                return Err(ForestaError::OperationPaused);
+            }
+            Ok(())
+        }
+
+        /// Hands role administration over to a k-of-n signer set and revokes ADMIN from every
+        /// holder. From then on roles and admin settings only change through approved proposals.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        pub fn configure_multisig(&mut self, signer_set: SignerSet) -> Result<(), ForestaError> {
+            if self.signer_set.is_some() {
+                return Err(ForestaError::MultisigRequired);
+            }
+            signer_set.validate()?;
+            // GOVERNANCE administers itself, so no account can grant it outside a proposal.
+            for role in [ADMIN, MINTER, CURATOR, EXECUTOR, TREASURER, GOVERNANCE] {
+                access_control::Internal::_set_role_admin(self, role, GOVERNANCE);
+            }
+            for admin in core::mem::take(&mut self.admins) {
+                access_control::Internal::_do_revoke_role(self, ADMIN, Some(admin));
+            }
+            self.signer_set = Some(signer_set);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_signer_set(&self) -> Option<SignerSet> {
+            self.signer_set.clone()
+        }
+
+        #[ink(message)]
+        pub fn propose(&mut self, action: AdminAction, ttl_ms: u64) -> Result<u32, ForestaError> {
+            self.submit_proposal(action, ttl_ms)
+        }
+
+        #[ink(message)]
+        pub fn approve_proposal(&mut self, proposal_id: u32) -> Result<(), ForestaError> {
+            self.add_approval(proposal_id)
+        }
+
+        /// Withdraws a proposal. Only its proposer can cancel it.
+        #[ink(message)]
+        pub fn cancel_proposal(&mut self, proposal_id: u32) -> Result<(), ForestaError> {
+            self.withdraw_proposal(proposal_id)
+        }
+
+        #[ink(message)]
+        pub fn get_proposal(&self, proposal_id: u32) -> Option<Proposal<AdminAction>> {
+            self.proposals.get(proposal_id)
+        }
+
+        // Single-key path for admin actions, only open while no signer set is configured.
+        fn direct_admin_action(&mut self, action: AdminAction) -> Result<(), ForestaError> {
+            if self.signer_set.is_some() {
+                return Err(ForestaError::MultisigRequired);
+            }
+            self.validate_action(&action)?;
+            self.apply_action(action);
+            Ok(())
+        }
+    }
+
+    impl Multisig for ExecutionNFT {
+        type Action = AdminAction;
+        type Error = ForestaError;
+
+        fn signer_set(&self) -> Option<SignerSet> {
+            self.signer_set.clone()
+        }
+
+        fn load_proposal(&self, proposal_id: u32) -> Option<Proposal<AdminAction>> {
+            self.proposals.get(proposal_id)
+        }
+
+        fn store_proposal(&mut self, proposal_id: u32, proposal: &Proposal<AdminAction>) {
+            self.proposals.insert(proposal_id, proposal);
+        }
+
+        fn remove_proposal(&mut self, proposal_id: u32) {
+            self.proposals.remove(proposal_id);
+        }
+
+        fn take_proposal_id(&mut self) -> u32 {
+            let proposal_id = self.next_proposal_id;
+            self.next_proposal_id = proposal_id.saturating_add(1);
+            proposal_id
+        }
+
+        fn caller(&self) -> AccountId {
+            self.env().caller()
+        }
+
+        fn now(&self) -> u64 {
+            self.env().block_timestamp()
+        }
+
+        fn validate_action(&self, action: &AdminAction) -> Result<(), ForestaError> {
+            if let AdminAction::SetExecuteNftContract(_) = action {
+                return Err(ForestaError::UnsupportedAction);
+            }
+            action.validate()
+        }
+
+        fn apply_action(&mut self, action: AdminAction) {
+            match action {
+                AdminAction::GrantRole(role, account) => {
+                    access_control::Internal::_setup_role(self, role, Some(account));
+                }
+                AdminAction::RevokeRole(role, account) => {
+                    access_control::Internal::_do_revoke_role(self, role, Some(account));
+                }
+                AdminAction::SetSignerSet(signer_set) => self.signer_set = Some(signer_set),
+                AdminAction::Unpause(operation) => self.resume(operation),
+                AdminAction::Pause(operation) => self.halt(operation),
+                AdminAction::SetCollectionAttribute(key, value) => {
+                    let collection_id = psp34::PSP34Impl::collection_id(self);
+                    metadata::Internal::_set_attribute(self, collection_id, key, value);
+                }
+                AdminAction::SetAlgoNftContract(contract) => {
+                    self.algo_nft_contract = Some(contract);
+                }
+                // Refused by `validate_action`.
+                AdminAction::SetExecuteNftContract(_) => (),
+            }
+        }
+
+        fn emit_proposal_event(&self, proposal_id: u32, event: ProposalEvent) {
+            match event {
+                ProposalEvent::Created {
+                    proposer,
+                    expires_at,
+                } => {
+                    self.env().emit_event(ProposalCreated {
+                        proposal_id,
+                        proposer,
+                        expires_at,
+                    })
+                }
+                ProposalEvent::Executed => self.env().emit_event(ProposalExecuted { proposal_id }),
+                ProposalEvent::Cancelled => {
+                    self.env().emit_event(ProposalCancelled { proposal_id })
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use foresta_types::metadata::BASE_URI_KEY;
+        use ink::env::{
+            test,
+            DefaultEnvironment,
+        };
+
+        fn accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        fn attribute(contract: &ExecutionNFT, id: Id, key: &str) -> Option<String> {
+            PSP34Metadata::get_attribute(contract, id, String::from(key))
+        }
+
+        // PSP34 ENUMERABLE TESTS
+        #[ink::test]
+        fn enumerable_tracks_mints_and_transfers() {
+            let accounts = accounts();
+            let mut contract = ExecutionNFT::new();
+            let first = contract.mint(accounts.alice).unwrap();
+            let second = contract.mint(accounts.bob).unwrap();
+
+            assert_eq!(PSP34::total_supply(&contract), 2);
+            assert_eq!(
+                PSP34Enumerable::token_by_index(&contract, 0),
+                Ok(first.clone())
+            );
+            assert_eq!(
+                PSP34Enumerable::owners_token_by_index(&contract, accounts.bob, 0),
+                Ok(second)
+            );
+
+            assert!(
+                PSP34::transfer(&mut contract, accounts.bob, first.clone(), Vec::new()).is_ok()
+            );
+            assert!(PSP34Enumerable::owners_token_by_index(&contract, accounts.alice, 0).is_err());
+            assert_eq!(
+                PSP34Enumerable::owners_token_by_index(&contract, accounts.bob, 1),
+                Ok(first)
+            );
+        }
+
+        // BURN TESTS
+        #[test]
+        fn burn_tolerates_already_unlinked_execute_nfts() {
+            assert_eq!(unlink_result(Ok(())), Ok(()));
+            assert_eq!(
+                unlink_result(Err(CallError::Callee(ForestaError::ExecuteNftNotLinked))),
+                Ok(())
+            );
+            assert_eq!(
+                unlink_result(Err(CallError::Callee(ForestaError::NotAuthorized))),
+                Err(ForestaError::NotAuthorized)
+            );
+            assert_eq!(
+                unlink_result(Err(CallError::Reverted)),
+                Err(ForestaError::CallFailed)
+            );
+        }
+
+        #[ink::test]
+        fn burn_needs_owner_and_algorithm_contract() {
+            let accounts = accounts();
+            let mut contract = ExecutionNFT::new();
+            let exec_id = contract.mint(accounts.alice).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.burn(exec_id.clone()),
+                Err(ForestaError::NotAuthorized)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(
+                contract.burn(exec_id.clone()),
+                Err(ForestaError::AlgorithmNftNotSet)
+            );
+            assert_eq!(contract.burn(Id::U64(9)), Err(ForestaError::TokenNotFound));
+        }
+
+        // MULTISIG TESTS
+        #[ink::test]
+        fn multisig_revokes_admins_and_routes_settings_through_proposals() {
+            let accounts = accounts();
+            let mut contract = ExecutionNFT::new();
+            assert!(contract
+                .configure_multisig(SignerSet {
+                    signers: ink::prelude::vec![accounts.alice, accounts.bob],
+                    threshold: 2,
+                })
+                .is_ok());
+            assert!(!AccessControl::has_role(
+                &contract,
+                ADMIN,
+                Some(accounts.alice)
+            ));
+            assert_eq!(
+                AccessControl::grant_role(&mut contract, GOVERNANCE, Some(accounts.alice)),
+                Err(AccessControlError::MissingRole)
+            );
+            assert_eq!(
+                contract.set_algo_nft_contract(accounts.django),
+                Err(ForestaError::NotAuthorized)
+            );
+
+            let proposal_id = contract
+                .propose(AdminAction::SetAlgoNftContract(accounts.django), 60_000)
+                .unwrap();
+            set_caller(accounts.bob);
+            assert!(contract.approve_proposal(proposal_id).is_ok());
+            assert_eq!(contract.get_algo_nft_contract(), Some(accounts.django));
+        }
+
+        // PSP34 METADATA TESTS
+        #[ink::test]
+        fn collection_attributes_are_set_by_admin() {
+            let mut contract = ExecutionNFT::new();
+            let collection_id = PSP34::collection_id(&contract);
+            assert_eq!(
+                attribute(&contract, collection_id.clone(), NAME_KEY),
+                Some(String::from("Foresta Execution"))
+            );
+            assert_eq!(
+                attribute(&contract, collection_id.clone(), SYMBOL_KEY),
+                Some(String::from("FEXEC"))
+            );
+
+            assert!(contract
+                .set_collection_attribute(String::from(BASE_URI_KEY), String::from("ipfs://base/"))
+                .is_ok());
+            assert_eq!(
+                attribute(&contract, collection_id, BASE_URI_KEY),
+                Some(String::from("ipfs://base/"))
+            );
+
+            set_caller(accounts().bob);
+            assert_eq!(
+                contract.set_collection_attribute(String::from(NAME_KEY), String::from("Other")),
+                Err(ForestaError::NotAuthorized)
+            );
+        }
+
+        #[ink::test]
+        fn token_attributes_need_minter_and_an_existing_token() {
+            let mut contract = ExecutionNFT::new();
+            let exec_id = contract.mint(accounts().alice).unwrap();
+
+            assert!(contract
+                .set_token_attribute(
+                    exec_id.clone(),
+                    String::from("algorithm"),
+                    String::from("1")
+                )
+                .is_ok());
+            assert_eq!(
+                attribute(&contract, exec_id.clone(), "algorithm"),
+                Some(String::from("1"))
+            );
+            assert_eq!(
+                contract.set_token_attribute(Id::U64(7), String::from("algorithm"), String::new()),
+                Err(ForestaError::TokenNotFound)
+            );
+
+            set_caller(accounts().bob);
+            assert_eq!(
+                contract.set_token_attribute(exec_id, String::from("algorithm"), String::new()),
+                Err(ForestaError::NotAuthorized)
+            );
+        }
     }
-}
\ No newline at end of file
+}
@@ -4,8 +4,7 @@
 // extern crate schrodinger_contract;
 
 pub mod execute_nft;
-pub mod error;
 
 pub use execute_nft::*;
-pub use error::AlgoExecuteError;
+pub use foresta_types::ForestaError;
 // pub use schrodinger_contract::*;
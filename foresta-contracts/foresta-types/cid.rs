@@ -0,0 +1,338 @@
+use crate::ForestaError;
+use alloc::{
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+use scale::{
+    Decode,
+    Encode,
+};
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE36_ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+const CIDV0_LEN: usize = 46;
+const SHA2_256: u64 = 0x12;
+const MAX_VARINT_LEN: usize = 9;
+
+/// Content codecs a CIDv1 may reference: raw, dag-pb, dag-cbor and dag-json.
+const CODECS: [u64; 4] = [0x55, 0x70, 0x71, 0x0129];
+
+/// Multihash functions with their digest length: sha2-256, sha2-512, sha3-256, blake3 and
+/// blake2b-256.
+const MULTIHASHES: [(u64, usize); 5] = [
+    (SHA2_256, 32),
+    (0x13, 64),
+    (0x16, 32),
+    (0x1e, 32),
+    (0xb220, 32),
+];
+
+/// An IPFS content identifier in binary form: the bare multihash for CIDv0 and
+/// `<version><codec><multihash>` for CIDv1. Displays as base58btc for CIDv0 and as base32
+/// for CIDv1, whatever base it was parsed from.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Cid(Vec<u8>);
+
+impl Cid {
+    /// Parses a base58btc CIDv0 or a base32 or base36 multibase CIDv1.
+    pub fn parse(text: &str) -> Result<Self, ForestaError> {
+        if text.len() == CIDV0_LEN && text.starts_with("Qm") {
+            let cid =
+                Cid(decode_base_x(text.as_bytes(), BASE58_ALPHABET)
+                    .ok_or(ForestaError::InvalidCid)?);
+            if !cid.is_v0() {
+                return Err(ForestaError::InvalidCid);
+            }
+            return Ok(cid);
+        }
+
+        let mut chars = text.chars();
+        let bytes = match chars.next() {
+            Some('b') => decode_base32(chars.as_str().as_bytes()),
+            Some('B') => decode_base32(chars.as_str().to_ascii_lowercase().as_bytes()),
+            Some('k') => decode_base_x(chars.as_str().as_bytes(), BASE36_ALPHABET),
+            Some('K') => {
+                decode_base_x(
+                    chars.as_str().to_ascii_lowercase().as_bytes(),
+                    BASE36_ALPHABET,
+                )
+            }
+            _ => None,
+        };
+        // A multibase string always holds a CIDv1, never a bare multihash.
+        let cid = Cid(bytes.ok_or(ForestaError::InvalidCid)?);
+        if !cid.is_valid_v1() {
+            return Err(ForestaError::InvalidCid);
+        }
+        Ok(cid)
+    }
+
+    pub fn version(&self) -> u8 {
+        if self.is_v0() {
+            0
+        } else {
+            1
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn is_v0(&self) -> bool {
+        self.0.len() == 34 && self.0[0] == SHA2_256 as u8 && self.0[1] == 32
+    }
+
+    fn is_valid_v1(&self) -> bool {
+        let mut bytes = self.0.as_slice();
+        read_varint(&mut bytes) == Some(1)
+            && read_varint(&mut bytes).map_or(false, |codec| CODECS.contains(&codec))
+            && is_valid_multihash(bytes)
+    }
+}
+
+impl fmt::Display for Cid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_v0() {
+            f.write_str(&encode_base_x(&self.0, BASE58_ALPHABET))
+        } else {
+            write!(f, "b{}", encode_base32(&self.0))
+        }
+    }
+}
+
+fn is_valid_multihash(mut bytes: &[u8]) -> bool {
+    let (Some(code), Some(len)) = (read_varint(&mut bytes), read_varint(&mut bytes)) else {
+        return false;
+    };
+    MULTIHASHES
+        .iter()
+        .any(|&(known, digest_len)| known == code && digest_len as u64 == len)
+        && bytes.len() as u64 == len
+}
+
+// Unsigned LEB128 as used by multiformats, at most 9 bytes long.
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (index, byte) in bytes.iter().enumerate().take(MAX_VARINT_LEN) {
+        value |= u64::from(byte & 0x7f) << (7 * index);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[index + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+// Big number base conversion shared by base58btc and base36. Leading zero digits stand for
+// leading zero bytes.
+fn decode_base_x(text: &[u8], alphabet: &[u8]) -> Option<Vec<u8>> {
+    if text.is_empty() {
+        return None;
+    }
+    let base = alphabet.len() as u32;
+    let zeros = text.iter().take_while(|&&c| c == alphabet[0]).count();
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len());
+    for &c in &text[zeros..] {
+        let mut carry = alphabet.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * base;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut decoded = vec![0u8; zeros];
+    decoded.extend(bytes.iter().rev());
+    Some(decoded)
+}
+
+fn encode_base_x(bytes: &[u8], alphabet: &[u8]) -> String {
+    let base = alphabet.len() as u32;
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 2);
+    for &byte in &bytes[zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % base) as u8;
+            carry /= base;
+        }
+        while carry > 0 {
+            digits.push((carry % base) as u8);
+            carry /= base;
+        }
+    }
+    let mut text = String::with_capacity(zeros + digits.len());
+    text.extend(core::iter::repeat(alphabet[0] as char).take(zeros));
+    text.extend(digits.iter().rev().map(|&d| alphabet[d as usize] as char));
+    text
+}
+
+// RFC 4648 base32 without padding, lowercase. `=` is not in the alphabet, so padded input is
+// rejected along with lengths no byte string encodes to (1, 3 or 6 characters past a multiple
+// of 8).
+fn decode_base32(text: &[u8]) -> Option<Vec<u8>> {
+    if text.is_empty() || matches!(text.len() % 8, 1 | 3 | 6) {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &c in text {
+        let value = BASE32_ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    // Leftover bits are padding and must be zero.
+    if buffer & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+    Some(bytes)
+}
+
+fn encode_base32(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity((bytes.len() * 8 + 4) / 5);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            text.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        text.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{
+        format,
+        string::ToString,
+    };
+
+    const CID_V0: &str = "QmY7Yh4UquoXHLPFo2XbhXkhBvFoPwmQUSa92pxnxjQuPU";
+    const CID_V1: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+    const CID_V1_BASE36: &str = "k2jmtxw8rjh1z69c6not3wtdxb0u3urbzhyll1t9jg6ox26dhi5sfi1m";
+    const CID_V1_RAW: &str = "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+
+    #[test]
+    fn v0_round_trips() {
+        let cid = Cid::parse(CID_V0).unwrap();
+        assert_eq!(cid.version(), 0);
+        assert_eq!(cid.as_bytes().len(), 34);
+        assert_eq!(&cid.as_bytes()[..2], &[0x12, 0x20]);
+        assert_eq!(cid.to_string(), CID_V0);
+    }
+
+    #[test]
+    fn v1_round_trips() {
+        for text in [CID_V1, CID_V1_RAW] {
+            let cid = Cid::parse(text).unwrap();
+            assert_eq!(cid.version(), 1);
+            assert_eq!(cid.to_string(), text);
+        }
+        assert_eq!(
+            &Cid::parse(CID_V1).unwrap().as_bytes()[..4],
+            &[0x01, 0x70, 0x12, 0x20]
+        );
+    }
+
+    #[test]
+    fn v1_in_other_bases_displays_as_base32() {
+        let uppercase = CID_V1.to_ascii_uppercase();
+        for text in [CID_V1_BASE36, uppercase.as_str()] {
+            assert_eq!(Cid::parse(text).unwrap().to_string(), CID_V1);
+        }
+    }
+
+    #[test]
+    fn multibase_bare_multihash_is_rejected() {
+        let multihash = Cid::parse(CID_V0).unwrap();
+        let text = format!("b{}", encode_base32(multihash.as_bytes()));
+        assert_eq!(Cid::parse(&text), Err(ForestaError::InvalidCid));
+    }
+
+    #[test]
+    fn unsupported_codec_is_rejected() {
+        // git-raw (0x78) over the same sha2-256 digest as `CID_V1`.
+        assert_eq!(
+            Cid::parse("baf4beigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"),
+            Err(ForestaError::InvalidCid)
+        );
+    }
+
+    #[test]
+    fn malformed_text_is_rejected() {
+        let truncated = &CID_V1[..CID_V1.len() - 1];
+        let padded = format!("{}=", CID_V1);
+        let wrong_alphabet = CID_V1.replace('y', "1");
+        let invalid_v0 = CID_V0.replace('Y', "0");
+        for text in [
+            "",
+            "b",
+            "z",
+            "Qm",
+            truncated,
+            padded.as_str(),
+            wrong_alphabet.as_str(),
+            invalid_v0.as_str(),
+        ] {
+            assert_eq!(Cid::parse(text), Err(ForestaError::InvalidCid), "{}", text);
+        }
+    }
+
+    #[test]
+    fn base32_lengths_without_byte_length_are_rejected() {
+        for len in 1..=16 {
+            let text = "a".repeat(len);
+            let expected = !matches!(len % 8, 1 | 3 | 6);
+            assert_eq!(
+                decode_base32(text.as_bytes()).is_some(),
+                expected,
+                "{}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn non_canonical_base32_is_rejected() {
+        // Flipping the unused low bits of the last character keeps the bytes the same.
+        let last = CID_V1.chars().last().unwrap();
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&c| c as char == last)
+            .unwrap();
+        let non_canonical = format!(
+            "{}{}",
+            &CID_V1[..CID_V1.len() - 1],
+            BASE32_ALPHABET[value | 1] as char
+        );
+        assert_ne!(non_canonical, CID_V1);
+        assert_eq!(Cid::parse(&non_canonical), Err(ForestaError::InvalidCid));
+    }
+}
@@ -0,0 +1,117 @@
+use ink::{
+    env::Error as EnvError,
+    LangError,
+};
+use openbrush::contracts::{
+    access_control::AccessControlError,
+    ownable::OwnableError,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Errors returned by the AS-NFT and EA-NFT contracts.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ForestaError {
+    NotAuthorized,
+    /// The caller already holds the role being granted.
+    RoleRedundant,
+    TokenNotFound,
+    AlgorithmCidNotFound,
+    InvalidSignerSet,
+    NotSigner,
+    ProposalNotFound,
+    ProposalExpired,
+    AlreadyApproved,
+    MultisigRequired,
+    OperationPaused,
+    ExecuteNftAlreadyLinked,
+    ExecuteNftNotLinked,
+    /// Algorithm metadata is missing a required field or exceeds a length limit.
+    InvalidMetadata,
+    /// Not a CIDv0, or a base32 or base36 CIDv1 with a supported codec and multihash.
+    InvalidCid,
+    /// The AS-NFT still has EA-NFTs linked to it.
+    ExecuteNftsActive,
+    AlgorithmBurned,
+    /// The EA-NFT contract doesn't know the AS-NFT contract yet.
+    AlgorithmNftNotSet,
+    /// A call into another NFT contract failed without returning an error of its own.
+    CallFailed,
+    /// The token already has `MAX_TOKEN_ATTRIBUTES` custom attributes.
+    TooManyAttributes,
+    /// The admin action targets a setting this contract doesn't have.
+    UnsupportedAction,
+}
+
+impl From<OwnableError> for ForestaError {
+    fn from(_err: OwnableError) -> Self {
+        ForestaError::NotAuthorized
+    }
+}
+
+impl From<AccessControlError> for ForestaError {
+    fn from(err: AccessControlError) -> Self {
+        match err {
+            AccessControlError::RoleRedundant => ForestaError::RoleRedundant,
+            AccessControlError::InvalidCaller | AccessControlError::MissingRole => {
+                ForestaError::NotAuthorized
+            }
+        }
+    }
+}
+
+/// Why a call into another Foresta contract failed.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum CallError {
+    /// The callee ran and returned this error.
+    Callee(ForestaError),
+    /// The callee trapped or reverted.
+    Reverted,
+    /// The callee doesn't have the message or couldn't decode its arguments.
+    CouldNotReadInput,
+    /// There is no contract at the called address.
+    NotCallable,
+    /// The callee returned something other than the expected type.
+    DecodeFailed,
+    /// Any other failure reported by the environment.
+    Environment,
+}
+
+impl From<EnvError> for CallError {
+    fn from(err: EnvError) -> Self {
+        match err {
+            EnvError::CalleeTrapped | EnvError::CalleeReverted => CallError::Reverted,
+            EnvError::CodeNotFound | EnvError::NotCallable => CallError::NotCallable,
+            EnvError::Decode(_) => CallError::DecodeFailed,
+            _ => CallError::Environment,
+        }
+    }
+}
+
+impl From<LangError> for CallError {
+    fn from(_err: LangError) -> Self {
+        CallError::CouldNotReadInput
+    }
+}
+
+impl From<ForestaError> for CallError {
+    fn from(err: ForestaError) -> Self {
+        CallError::Callee(err)
+    }
+}
+
+/// Flattens the result of `try_invoke` on a message that can't fail.
+pub fn call_result<T>(result: Result<Result<T, LangError>, EnvError>) -> Result<T, CallError> {
+    Ok(result??)
+}
+
+/// Flattens the result of `try_invoke` on a message returning `Result<T, ForestaError>`.
+pub fn fallible_call_result<T>(
+    result: Result<Result<Result<T, ForestaError>, LangError>, EnvError>,
+) -> Result<T, CallError> {
+    Ok(result???)
+}
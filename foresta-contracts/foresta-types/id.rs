@@ -0,0 +1,34 @@
+use alloc::{
+    format,
+    string::String,
+};
+use openbrush::contracts::psp34::Id;
+
+/// Decimal for numeric ids, `0x` prefixed hex for byte ids.
+pub fn id_to_string(id: &Id) -> String {
+    match id {
+        Id::U8(id) => format!("{}", id),
+        Id::U16(id) => format!("{}", id),
+        Id::U32(id) => format!("{}", id),
+        Id::U64(id) => format!("{}", id),
+        Id::U128(id) => format!("{}", id),
+        Id::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+    }
+}
+
+/// Numeric value of an id, whatever integer width it was minted with.
+pub fn id_to_u128(id: &Id) -> Option<u128> {
+    match id {
+        Id::U8(id) => Some(u128::from(*id)),
+        Id::U16(id) => Some(u128::from(*id)),
+        Id::U32(id) => Some(u128::from(*id)),
+        Id::U64(id) => Some(u128::from(*id)),
+        Id::U128(id) => Some(*id),
+        Id::Bytes(_) => None,
+    }
+}
+
+/// The id as a `u8`, for storage still keyed by `u8` token ids.
+pub fn id_to_u8(id: &Id) -> Option<u8> {
+    id_to_u128(id).and_then(|id| u8::try_from(id).ok())
+}
@@ -0,0 +1,27 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Types shared by the AS-NFT, EA-NFT and Schrodinger contracts.
+
+extern crate alloc;
+
+pub mod cid;
+pub mod error;
+pub mod id;
+pub mod metadata;
+pub mod multisig;
+pub mod pause;
+pub mod roles;
+
+pub use cid::Cid;
+pub use error::{
+    CallError,
+    ForestaError,
+};
+pub use metadata::{
+    AlgorithmInfo,
+    AlgorithmMetadata,
+};
+pub use pause::{
+    Operation,
+    PausedOperations,
+};
@@ -0,0 +1,318 @@
+use crate::ForestaError;
+use alloc::{
+    string::String,
+    vec,
+    vec::Vec,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// Collection attribute holding the display name.
+pub const NAME_KEY: &str = "name";
+/// Collection attribute holding the ticker.
+pub const SYMBOL_KEY: &str = "symbol";
+/// Collection attribute that token URIs are resolved against.
+pub const BASE_URI_KEY: &str = "baseURI";
+
+/// Token attributes mirroring the short `AlgorithmMetadata` fields, so PSP34 marketplaces can
+/// show them. `authors` and `tags` hold JSON arrays. The schemas and extensions are documents
+/// of up to `MAX_DOCUMENT_LEN` bytes and are only served by `get_algorithm_metadata`.
+pub const TITLE_KEY: &str = "title";
+pub const AUTHORS_KEY: &str = "authors";
+pub const LICENSE_KEY: &str = "license";
+pub const RUNTIME_KEY: &str = "runtime";
+pub const ENTRYPOINT_KEY: &str = "entrypoint";
+pub const CONTENT_TYPE_KEY: &str = "contentType";
+pub const DOI_KEY: &str = "doi";
+pub const TAGS_KEY: &str = "tags";
+/// Most custom attributes a single token can carry besides the metadata ones.
+pub const MAX_TOKEN_ATTRIBUTES: usize = 16;
+
+/// Every attribute written from `AlgorithmMetadata`.
+pub const METADATA_ATTRIBUTE_KEYS: [&str; 8] = [
+    TITLE_KEY,
+    AUTHORS_KEY,
+    LICENSE_KEY,
+    RUNTIME_KEY,
+    ENTRYPOINT_KEY,
+    CONTENT_TYPE_KEY,
+    DOI_KEY,
+    TAGS_KEY,
+];
+
+pub const MAX_TITLE_LEN: usize = 128;
+pub const MAX_AUTHORS: usize = 16;
+pub const MAX_AUTHOR_LEN: usize = 128;
+pub const MAX_LICENSE_LEN: usize = 64;
+pub const MAX_RUNTIME_LEN: usize = 64;
+pub const MAX_ENTRYPOINT_LEN: usize = 256;
+pub const MAX_CONTENT_TYPE_LEN: usize = 128;
+pub const MAX_DOI_LEN: usize = 256;
+pub const MAX_TAGS: usize = 16;
+pub const MAX_TAG_LEN: usize = 32;
+/// Limit on each of the input schema, output schema and extensions documents.
+pub const MAX_DOCUMENT_LEN: usize = 4096;
+
+/// Description of an AS-NFT that marketplaces can display.
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct AlgorithmMetadata {
+    pub title: String,
+    pub authors: Vec<String>,
+    /// SPDX license identifier, e.g. `Apache-2.0`.
+    pub license: String,
+    /// Language and runtime the algorithm is written for, e.g. `python-3.11`.
+    pub runtime: String,
+    pub entrypoint: String,
+    /// JSON Schema of the execution input.
+    #[serde(default)]
+    pub input_schema: Option<String>,
+    /// JSON Schema of the execution output.
+    #[serde(default)]
+    pub output_schema: Option<String>,
+    #[serde(default)]
+    pub doi: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// MIME type of the decrypted algorithm.
+    pub content_type: String,
+    /// Free form JSON object for fields this schema doesn't cover.
+    #[serde(default)]
+    pub extensions: Option<String>,
+}
+
+impl AlgorithmMetadata {
+    pub fn validate(&self) -> Result<(), ForestaError> {
+        let valid = is_valid_text(&self.title, MAX_TITLE_LEN)
+            && !self.authors.is_empty()
+            && self.authors.len() <= MAX_AUTHORS
+            && self
+                .authors
+                .iter()
+                .all(|author| is_valid_text(author, MAX_AUTHOR_LEN))
+            && is_valid_spdx_id(&self.license)
+            && is_valid_text(&self.runtime, MAX_RUNTIME_LEN)
+            && is_valid_text(&self.entrypoint, MAX_ENTRYPOINT_LEN)
+            && self.input_schema.as_deref().map_or(true, is_json_object)
+            && self.output_schema.as_deref().map_or(true, is_json_object)
+            && self.doi.as_deref().map_or(true, is_valid_doi)
+            && self.tags.len() <= MAX_TAGS
+            && self.tags.iter().all(|tag| is_valid_text(tag, MAX_TAG_LEN))
+            && is_valid_content_type(&self.content_type)
+            && self.extensions.as_deref().map_or(true, is_json_object);
+        if !valid {
+            return Err(ForestaError::InvalidMetadata);
+        }
+        Ok(())
+    }
+
+    /// Token attributes for the metadata, keyed by the `*_KEY` constants. `None` means the
+    /// attribute is unset and has to be removed.
+    pub fn attributes(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            (TITLE_KEY, Some(self.title.clone())),
+            (AUTHORS_KEY, Some(json_array(&self.authors))),
+            (LICENSE_KEY, Some(self.license.clone())),
+            (RUNTIME_KEY, Some(self.runtime.clone())),
+            (ENTRYPOINT_KEY, Some(self.entrypoint.clone())),
+            (CONTENT_TYPE_KEY, Some(self.content_type.clone())),
+            (DOI_KEY, self.doi.clone()),
+            (
+                TAGS_KEY,
+                (!self.tags.is_empty()).then(|| json_array(&self.tags)),
+            ),
+        ]
+    }
+}
+
+fn json_array(items: &[String]) -> String {
+    serde_json::to_string(items).unwrap_or_default()
+}
+
+// Non blank, within `max_len` bytes and free of control characters.
+fn is_valid_text(text: &str, max_len: usize) -> bool {
+    !text.trim().is_empty() && text.len() <= max_len && !text.chars().any(char::is_control)
+}
+
+// Single SPDX identifier; license expressions belong in `extensions`.
+fn is_valid_spdx_id(license: &str) -> bool {
+    !license.is_empty()
+        && license.len() <= MAX_LICENSE_LEN
+        && license
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'-' || b == b'+')
+}
+
+// `10.<registrant>/<suffix>`
+fn is_valid_doi(doi: &str) -> bool {
+    is_valid_text(doi, MAX_DOI_LEN)
+        && doi
+            .strip_prefix("10.")
+            .and_then(|rest| rest.split_once('/'))
+            .map_or(false, |(registrant, suffix)| {
+                !registrant.is_empty() && !suffix.is_empty()
+            })
+}
+
+fn is_valid_content_type(content_type: &str) -> bool {
+    is_valid_text(content_type, MAX_CONTENT_TYPE_LEN)
+        && content_type
+            .split_once('/')
+            .map_or(false, |(kind, subtype)| {
+                !kind.is_empty() && !subtype.is_empty()
+            })
+}
+
+fn is_json_object(document: &str) -> bool {
+    document.len() <= MAX_DOCUMENT_LEN
+        && serde_json::from_str::<serde_json::Value>(document)
+            .map_or(false, |value| value.is_object())
+}
+
+/// What the Schrodinger contract needs to know about an AS-NFT to run it.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct AlgorithmInfo {
+    /// CID of the encrypted algorithm.
+    pub cid: String,
+    /// Bumped on every CID update, starting at 1.
+    pub version: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{
+        format,
+        string::ToString,
+    };
+
+    fn metadata() -> AlgorithmMetadata {
+        AlgorithmMetadata {
+            title: "Forest cover classifier".to_string(),
+            authors: vec!["Ada Lovelace".to_string(), "Alan Turing".to_string()],
+            license: "Apache-2.0".to_string(),
+            runtime: "python-3.11".to_string(),
+            entrypoint: "main.py".to_string(),
+            input_schema: Some("{\"type\":\"object\"}".to_string()),
+            output_schema: None,
+            doi: Some("10.5281/zenodo.1234567".to_string()),
+            tags: vec!["remote-sensing".to_string()],
+            content_type: "text/x-python".to_string(),
+            extensions: None,
+        }
+    }
+
+    fn rejects(change: impl FnOnce(&mut AlgorithmMetadata)) {
+        let mut metadata = metadata();
+        change(&mut metadata);
+        assert_eq!(metadata.validate(), Err(ForestaError::InvalidMetadata));
+    }
+
+    #[test]
+    fn valid_metadata_is_accepted() {
+        assert_eq!(metadata().validate(), Ok(()));
+
+        let mut minimal = metadata();
+        minimal.input_schema = None;
+        minimal.doi = None;
+        minimal.tags = Vec::new();
+        assert_eq!(minimal.validate(), Ok(()));
+    }
+
+    #[test]
+    fn limits_are_inclusive() {
+        let mut metadata = metadata();
+        metadata.title = "t".repeat(MAX_TITLE_LEN);
+        metadata.authors = vec!["a".repeat(MAX_AUTHOR_LEN); MAX_AUTHORS];
+        metadata.license = "L".repeat(MAX_LICENSE_LEN);
+        metadata.entrypoint = "e".repeat(MAX_ENTRYPOINT_LEN);
+        metadata.tags = vec!["t".repeat(MAX_TAG_LEN); MAX_TAGS];
+        metadata.extensions = Some(format!(
+            "{{\"x\":\"{}\"}}",
+            "x".repeat(MAX_DOCUMENT_LEN - 8)
+        ));
+        assert_eq!(metadata.validate(), Ok(()));
+    }
+
+    #[test]
+    fn oversized_fields_are_rejected() {
+        rejects(|m| m.title = "t".repeat(MAX_TITLE_LEN + 1));
+        rejects(|m| m.authors = vec!["a".to_string(); MAX_AUTHORS + 1]);
+        rejects(|m| m.authors[0] = "a".repeat(MAX_AUTHOR_LEN + 1));
+        rejects(|m| m.license = "L".repeat(MAX_LICENSE_LEN + 1));
+        rejects(|m| m.runtime = "r".repeat(MAX_RUNTIME_LEN + 1));
+        rejects(|m| m.entrypoint = "e".repeat(MAX_ENTRYPOINT_LEN + 1));
+        rejects(|m| m.tags = vec!["t".to_string(); MAX_TAGS + 1]);
+        rejects(|m| m.tags[0] = "t".repeat(MAX_TAG_LEN + 1));
+        rejects(|m| m.content_type = format!("text/{}", "x".repeat(MAX_CONTENT_TYPE_LEN)));
+        rejects(|m| m.doi = Some(format!("10.5281/{}", "x".repeat(MAX_DOI_LEN))));
+        rejects(|m| m.extensions = Some(format!("{{\"x\":\"{}\"}}", "x".repeat(MAX_DOCUMENT_LEN))));
+    }
+
+    #[test]
+    fn blank_and_control_text_is_rejected() {
+        rejects(|m| m.title = "   ".to_string());
+        rejects(|m| m.title = "line\nbreak".to_string());
+        rejects(|m| m.authors = Vec::new());
+        rejects(|m| m.authors[1] = String::new());
+        rejects(|m| m.runtime = "\u{7}".to_string());
+        rejects(|m| m.tags[0] = " ".to_string());
+    }
+
+    #[test]
+    fn malformed_identifiers_are_rejected() {
+        rejects(|m| m.license = "MIT OR Apache-2.0".to_string());
+        rejects(|m| m.license = String::new());
+        rejects(|m| m.doi = Some("zenodo.1234567".to_string()));
+        rejects(|m| m.doi = Some("10.5281/".to_string()));
+        rejects(|m| m.doi = Some("10./zenodo".to_string()));
+        rejects(|m| m.content_type = "python".to_string());
+        rejects(|m| m.content_type = "text/".to_string());
+    }
+
+    #[test]
+    fn documents_must_be_json_objects() {
+        rejects(|m| m.input_schema = Some("[]".to_string()));
+        rejects(|m| m.output_schema = Some("{\"type\":".to_string()));
+        rejects(|m| m.extensions = Some("\"text\"".to_string()));
+    }
+
+    #[test]
+    fn attributes_mirror_short_fields() {
+        let attributes = metadata().attributes();
+        let get = |key: &str| {
+            attributes
+                .iter()
+                .find(|(k, _)| *k == key)
+                .and_then(|(_, value)| value.clone())
+        };
+        assert_eq!(get(TITLE_KEY).as_deref(), Some("Forest cover classifier"));
+        assert_eq!(
+            get(AUTHORS_KEY).as_deref(),
+            Some("[\"Ada Lovelace\",\"Alan Turing\"]")
+        );
+        assert_eq!(get(TAGS_KEY).as_deref(), Some("[\"remote-sensing\"]"));
+        assert_eq!(get(DOI_KEY).as_deref(), Some("10.5281/zenodo.1234567"));
+
+        let mut minimal = metadata();
+        minimal.doi = None;
+        minimal.tags = Vec::new();
+        let attributes = minimal.attributes();
+        assert!(attributes
+            .iter()
+            .any(|(key, value)| *key == DOI_KEY && value.is_none()));
+        assert!(attributes
+            .iter()
+            .any(|(key, value)| *key == TAGS_KEY && value.is_none()));
+    }
+}
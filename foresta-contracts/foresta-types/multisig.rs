@@ -0,0 +1,233 @@
+use crate::{
+    roles::RoleType,
+    ForestaError,
+    Operation,
+};
+use alloc::{
+    string::String,
+    vec,
+    vec::Vec,
+};
+use ink::primitives::AccountId;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Upper bound on the number of signers in a signer set.
+pub const MAX_SIGNERS: usize = 16;
+
+/// Longest time a proposal stays open for approvals.
+pub const MAX_PROPOSAL_TTL_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Accounts allowed to approve admin proposals and how many approvals execute one.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct SignerSet {
+    pub signers: Vec<AccountId>,
+    pub threshold: u8,
+}
+
+impl SignerSet {
+    pub fn validate(&self) -> Result<(), ForestaError> {
+        let signers = self.signers.len();
+        if self.threshold == 0 || usize::from(self.threshold) > signers || signers > MAX_SIGNERS {
+            return Err(ForestaError::InvalidSignerSet);
+        }
+        for (i, signer) in self.signers.iter().enumerate() {
+            if self.signers[i + 1..].contains(signer) {
+                return Err(ForestaError::InvalidSignerSet);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn ensure_signer(&self, account: &AccountId) -> Result<(), ForestaError> {
+        if !self.signers.contains(account) {
+            return Err(ForestaError::NotSigner);
+        }
+        Ok(())
+    }
+}
+
+/// Pending admin action collecting approvals from the signer set.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Proposal<A> {
+    pub action: A,
+    pub proposer: AccountId,
+    pub approvals: Vec<AccountId>,
+    pub expires_at: u64,
+}
+
+impl<A> Proposal<A> {
+    // The proposer's approval is counted straight away.
+    pub fn new(action: A, proposer: AccountId, now: u64, ttl_ms: u64) -> Self {
+        Self {
+            action,
+            proposer,
+            approvals: vec![proposer],
+            expires_at: now.saturating_add(ttl_ms.min(MAX_PROPOSAL_TTL_MS)),
+        }
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    pub fn approve(&mut self, signer: AccountId, now: u64) -> Result<(), ForestaError> {
+        if self.is_expired(now) {
+            return Err(ForestaError::ProposalExpired);
+        }
+        if self.approvals.contains(&signer) {
+            return Err(ForestaError::AlreadyApproved);
+        }
+        self.approvals.push(signer);
+        Ok(())
+    }
+
+    // Signers removed from the set since approving no longer count.
+    pub fn is_approved(&self, signer_set: &SignerSet) -> bool {
+        let approvals = self
+            .approvals
+            .iter()
+            .filter(|account| signer_set.signers.contains(account))
+            .count();
+        approvals >= usize::from(signer_set.threshold)
+    }
+}
+
+/// Admin actions on the NFT contracts that need approval from the signer set. Each contract
+/// rejects the ones that don't apply to it with `UnsupportedAction`.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum AdminAction {
+    GrantRole(RoleType, AccountId),
+    RevokeRole(RoleType, AccountId),
+    SetSignerSet(SignerSet),
+    Unpause(Operation),
+    Pause(Operation),
+    SetCollectionAttribute(String, String),
+    SetAlgoNftContract(AccountId),
+    SetExecuteNftContract(AccountId),
+}
+
+impl AdminAction {
+    pub fn validate(&self) -> Result<(), ForestaError> {
+        if let AdminAction::SetSignerSet(signer_set) = self {
+            signer_set.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Lifecycle step of a proposal, emitted by the contract as an event.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ProposalEvent {
+    Created {
+        proposer: AccountId,
+        expires_at: u64,
+    },
+    Executed,
+    Cancelled,
+}
+
+/// Approval flow shared by every contract governed by a signer set. The contract stores the
+/// signer set and proposals, checks and applies its own actions and turns `ProposalEvent`s into
+/// ink! events; proposing, approving, cancelling and executing happen here.
+pub trait Multisig {
+    type Action;
+    type Error: From<ForestaError>;
+
+    fn signer_set(&self) -> Option<SignerSet>;
+
+    fn load_proposal(&self, proposal_id: u32) -> Option<Proposal<Self::Action>>;
+
+    fn store_proposal(&mut self, proposal_id: u32, proposal: &Proposal<Self::Action>);
+
+    fn remove_proposal(&mut self, proposal_id: u32);
+
+    /// Returns an unused proposal id and advances the counter.
+    fn take_proposal_id(&mut self) -> u32;
+
+    fn caller(&self) -> AccountId;
+
+    fn now(&self) -> u64;
+
+    fn validate_action(&self, action: &Self::Action) -> Result<(), Self::Error>;
+
+    fn apply_action(&mut self, action: Self::Action);
+
+    fn emit_proposal_event(&self, proposal_id: u32, event: ProposalEvent);
+
+    fn submit_proposal(&mut self, action: Self::Action, ttl_ms: u64) -> Result<u32, Self::Error> {
+        let signer_set = self.signer_set().ok_or(ForestaError::MultisigRequired)?;
+        let caller = self.caller();
+        signer_set.ensure_signer(&caller)?;
+        self.validate_action(&action)?;
+
+        let proposal_id = self.take_proposal_id();
+        let proposal = Proposal::new(action, caller, self.now(), ttl_ms);
+        self.emit_proposal_event(
+            proposal_id,
+            ProposalEvent::Created {
+                proposer: caller,
+                expires_at: proposal.expires_at,
+            },
+        );
+        self.settle_proposal(proposal_id, proposal, &signer_set)?;
+        Ok(proposal_id)
+    }
+
+    fn add_approval(&mut self, proposal_id: u32) -> Result<(), Self::Error> {
+        let signer_set = self.signer_set().ok_or(ForestaError::MultisigRequired)?;
+        let caller = self.caller();
+        signer_set.ensure_signer(&caller)?;
+        let mut proposal = self
+            .load_proposal(proposal_id)
+            .ok_or(ForestaError::ProposalNotFound)?;
+        proposal.approve(caller, self.now())?;
+        self.settle_proposal(proposal_id, proposal, &signer_set)
+    }
+
+    /// Withdraws a proposal. Only its proposer can cancel it.
+    fn withdraw_proposal(&mut self, proposal_id: u32) -> Result<(), Self::Error> {
+        let proposal = self
+            .load_proposal(proposal_id)
+            .ok_or(ForestaError::ProposalNotFound)?;
+        if proposal.proposer != self.caller() {
+            return Err(ForestaError::NotAuthorized.into());
+        }
+        self.remove_proposal(proposal_id);
+        self.emit_proposal_event(proposal_id, ProposalEvent::Cancelled);
+        Ok(())
+    }
+
+    // Executes the proposal once it has enough approvals, otherwise stores it.
+    fn settle_proposal(
+        &mut self,
+        proposal_id: u32,
+        proposal: Proposal<Self::Action>,
+        signer_set: &SignerSet,
+    ) -> Result<(), Self::Error> {
+        if !proposal.is_approved(signer_set) {
+            self.store_proposal(proposal_id, &proposal);
+            return Ok(());
+        }
+        self.remove_proposal(proposal_id);
+        // Checks are repeated as state may have changed since the proposal was made.
+        self.validate_action(&proposal.action)?;
+        self.apply_action(proposal.action);
+        self.emit_proposal_event(proposal_id, ProposalEvent::Executed);
+        Ok(())
+    }
+}
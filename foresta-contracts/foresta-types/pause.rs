@@ -0,0 +1,50 @@
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Operation that can be halted independently of the others.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum Operation {
+    Mint,
+    CidUpdate,
+    Transfer,
+    Execution,
+}
+
+impl Operation {
+    fn flag(self) -> u8 {
+        match self {
+            Operation::Mint => 1,
+            Operation::CidUpdate => 1 << 1,
+            Operation::Transfer => 1 << 2,
+            Operation::Execution => 1 << 3,
+        }
+    }
+}
+
+/// Set of currently paused operations.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, Default)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct PausedOperations(u8);
+
+impl PausedOperations {
+    pub fn is_paused(&self, operation: Operation) -> bool {
+        self.0 & operation.flag() != 0
+    }
+
+    pub fn pause(&mut self, operation: Operation) {
+        self.0 |= operation.flag();
+    }
+
+    pub fn unpause(&mut self, operation: Operation) {
+        self.0 &= !operation.flag();
+    }
+}
@@ -0,0 +1,19 @@
+//! Roles shared by the AS-NFT, EA-NFT and Schrodinger contracts. All three use openbrush
+//! `AccessControl` with these identifiers, so one account setup works for all of them.
+
+pub use openbrush::contracts::access_control::RoleType;
+use openbrush::contracts::access_control::DEFAULT_ADMIN_ROLE;
+
+/// Grants and revokes every other role.
+pub const ADMIN: RoleType = DEFAULT_ADMIN_ROLE;
+/// Mints AS-NFTs and EA-NFTs.
+pub const MINTER: RoleType = ink::selector_id!("MINTER");
+/// Maintains algorithm metadata, CIDs and the linked execute NFTs.
+pub const CURATOR: RoleType = ink::selector_id!("CURATOR");
+/// Automated service that runs algorithms on the Schrodinger contract.
+pub const EXECUTOR: RoleType = ink::selector_id!("EXECUTOR");
+/// Manages execution budgets on the Schrodinger contract.
+pub const TREASURER: RoleType = ink::selector_id!("TREASURER");
+/// Admin of every role once a signer set is configured. No account holds it, so roles can only
+/// change through approved proposals.
+pub const GOVERNANCE: RoleType = ink::selector_id!("GOVERNANCE");
@@ -0,0 +1,100 @@
+use crate::{
+    deposit::DatabaseAuth,
+    http::{
+        HttpPolicy,
+        MAX_HTTP_ATTEMPTS,
+        QUERY_BUDGET_MS,
+    },
+    ownership::OwnershipBackend,
+};
+use alloc::string::String;
+use ink::primitives::AccountId;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Runtime configuration that can be changed without redeploying the contract.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ContractConfig {
+    pub contract_id: String,
+    pub rpc_api: String,
+    pub ipfs_endpoint: String,
+    pub database_endpoint: String,
+    pub algo_nft_contract: AccountId,
+    pub execute_nft_contract: AccountId,
+    pub ownership_backend: OwnershipBackend,
+    pub http_policy: HttpPolicy,
+}
+
+/// Configuration entry changed by an owner, reported in `ConfigChanged`.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ConfigField {
+    ContractId,
+    RpcApi,
+    IpfsEndpoint,
+    DatabaseEndpoint,
+    DatabaseAuth,
+    AlgoNftContract,
+    ExecuteNftContract,
+    OwnershipBackend,
+    HttpPolicy,
+}
+
+/// Accepts `http(s)://` URLs with a non-empty host.
+pub fn is_valid_endpoint(url: &str) -> bool {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+    matches!(rest, Some(host) if !host.is_empty() && !host.starts_with('/'))
+}
+
+/// 20 byte hex address, with or without the `0x` prefix.
+pub fn is_valid_evm_address(address: &str) -> bool {
+    let hex = address.strip_prefix("0x").unwrap_or(address);
+    hex.len() == 40 && hex.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+// `contract_id` is only read by the EVM backend; the Substrate backends address the AS-NFT
+// collection through `algo_nft_contract` instead.
+pub fn is_valid_contract_id(backend: &OwnershipBackend, contract_id: &str) -> bool {
+    match backend {
+        OwnershipBackend::EvmRpc => is_valid_evm_address(contract_id),
+        _ => contract_id.is_empty() || is_valid_evm_address(contract_id),
+    }
+}
+
+pub fn is_valid_linked_contract(contract: &AccountId, this: &AccountId) -> bool {
+    contract != &AccountId::from([0u8; 32]) && contract != this
+}
+
+pub fn is_valid_backend(backend: &OwnershipBackend, contract_id: &str) -> bool {
+    match backend {
+        OwnershipBackend::EvmRpc => is_valid_evm_address(contract_id),
+        OwnershipBackend::SubstrateRpc { endpoint } => is_valid_endpoint(endpoint),
+        OwnershipBackend::CrossContract => true,
+    }
+}
+
+/// Minimum length of an HMAC secret for database requests.
+pub const MIN_HMAC_SECRET_LEN: usize = 32;
+
+// Bearer tokens end up in a header line, so they must not be able to inject another one.
+pub fn is_valid_database_auth(auth: &DatabaseAuth) -> bool {
+    match auth {
+        DatabaseAuth::None => true,
+        DatabaseAuth::Bearer(token) => {
+            !token.is_empty() && token.bytes().all(|b| b.is_ascii_graphic())
+        }
+        DatabaseAuth::Hmac(secret) => secret.len() >= MIN_HMAC_SECRET_LEN,
+    }
+}
+
+// Retries have to end before Phala cuts the query off.
+pub fn is_valid_http_policy(policy: &HttpPolicy) -> bool {
+    (1..=MAX_HTTP_ATTEMPTS).contains(&policy.max_attempts)
+        && policy.deadline_ms > 0
+        && policy.deadline_ms < QUERY_BUDGET_MS
+}
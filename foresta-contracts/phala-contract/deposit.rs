@@ -0,0 +1,184 @@
+use crate::{
+    error::PhalaError,
+    http::HttpPolicy,
+};
+use alloc::{
+    format,
+    string::{
+        String,
+        ToString,
+    },
+    vec,
+    vec::Vec,
+};
+use foresta_types::id::id_to_string;
+use ink::{
+    env::hash::{
+        HashOutput,
+        Sha2x256,
+    },
+    primitives::AccountId,
+};
+use openbrush::contracts::psp34::Id;
+use scale::{
+    Decode,
+    Encode,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// Version of the deposit request and receipt documents exchanged with the database.
+pub const DEPOSIT_SCHEMA_VERSION: u16 = 1;
+
+/// Decrypted algorithm content is always UTF-8 text.
+pub const DEPOSIT_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+
+/// Header carrying the hex encoded HMAC-SHA256 of the request body.
+pub const HMAC_HEADER: &str = "X-Foresta-Hmac-Sha256";
+
+const HMAC_BLOCK_SIZE: usize = 64;
+
+/// Credentials attached to requests sent to the database endpoint.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum DatabaseAuth {
+    #[default]
+    None,
+    Bearer(String),
+    Hmac(Vec<u8>),
+}
+
+impl DatabaseAuth {
+    pub fn headers(&self, body: &[u8]) -> Vec<(String, String)> {
+        let mut headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+        match self {
+            DatabaseAuth::None => (),
+            DatabaseAuth::Bearer(token) => {
+                headers.push(("Authorization".to_string(), format!("Bearer {}", token)));
+            }
+            DatabaseAuth::Hmac(secret) => {
+                headers.push((
+                    HMAC_HEADER.to_string(),
+                    hex::encode(hmac_sha256(secret, body)),
+                ));
+            }
+        }
+        headers
+    }
+}
+
+/// Document posted to the database for every deposit or execution.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct DepositRequest<'a> {
+    pub schema_version: u16,
+    pub algo_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exec_id: Option<String>,
+    pub caller: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub algorithm_version: Option<u32>,
+    pub content_type: &'static str,
+    pub payload_hash: String,
+    pub content: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<&'a str>,
+}
+
+impl<'a> DepositRequest<'a> {
+    pub fn new(algo_id: &Id, caller: &AccountId, content: &'a str, payload_hash: [u8; 32]) -> Self {
+        Self {
+            schema_version: DEPOSIT_SCHEMA_VERSION,
+            algo_id: id_to_string(algo_id),
+            exec_id: None,
+            caller: format!("0x{}", hex::encode(caller)),
+            algorithm_version: None,
+            content_type: DEPOSIT_CONTENT_TYPE,
+            payload_hash: format!("0x{}", hex::encode(payload_hash)),
+            content,
+            input: None,
+        }
+    }
+
+    pub fn with_exec_id(mut self, exec_id: &Id) -> Self {
+        self.exec_id = Some(id_to_string(exec_id));
+        self
+    }
+
+    pub fn with_algorithm_version(mut self, version: Option<u32>) -> Self {
+        self.algorithm_version = version;
+        self
+    }
+
+    pub fn with_input(mut self, input: &'a str) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, PhalaError> {
+        serde_json::to_vec(self).map_err(|_| PhalaError::DatabaseError)
+    }
+}
+
+/// Document the database answers a deposit with. `output` is only set for executions.
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct DepositReceipt {
+    pub schema_version: u16,
+    pub deposit_id: String,
+    #[serde(default)]
+    pub output: Option<String>,
+}
+
+impl DepositReceipt {
+    pub fn parse(body: &[u8]) -> Result<Self, PhalaError> {
+        let body = core::str::from_utf8(body).map_err(|_| PhalaError::DatabaseResponseNotUtf8)?;
+        let receipt: Self =
+            serde_json::from_str(body).map_err(|_| PhalaError::DatabaseResponseMalformed)?;
+        if receipt.schema_version != DEPOSIT_SCHEMA_VERSION {
+            return Err(PhalaError::DatabaseSchemaMismatch);
+        }
+        Ok(receipt)
+    }
+}
+
+/// Posts `request` to a REST endpoint speaking the deposit protocol and parses its receipt.
+/// A deposit may be recorded twice if it is resent, so it is never retried.
+pub fn post_deposit(
+    endpoint: &str,
+    auth: &DatabaseAuth,
+    http_policy: &HttpPolicy,
+    request: &DepositRequest,
+) -> Result<DepositReceipt, PhalaError> {
+    let body = request.to_json()?;
+    let headers = auth.headers(&body);
+    let response = http_policy.post(String::from(endpoint), body, headers, false)?;
+    DepositReceipt::parse(&response.body)
+}
+
+pub fn hmac_sha256(secret: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; HMAC_BLOCK_SIZE];
+    if secret.len() > HMAC_BLOCK_SIZE {
+        key[..32].copy_from_slice(&sha256(secret));
+    } else {
+        key[..secret.len()].copy_from_slice(secret);
+    }
+
+    let mut inner = Vec::with_capacity(HMAC_BLOCK_SIZE + message.len());
+    inner.extend(key.iter().map(|b| b ^ 0x36));
+    inner.extend_from_slice(message);
+
+    let mut outer = Vec::with_capacity(HMAC_BLOCK_SIZE + 32);
+    outer.extend(key.iter().map(|b| b ^ 0x5c));
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
+
+pub fn sha256(input: &[u8]) -> [u8; 32] {
+    let mut output = <Sha2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Sha2x256>(input, &mut output);
+    output
+}
@@ -1,29 +1,96 @@
+use foresta_types::{
+    CallError,
+    ForestaError,
+};
 pub use scale::{Decode, Encode};
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub enum PhalaError {
-    EcdhInvalidSecretKey,
-    EcdhInvalidPublicKey,
-    AESCannotEncrypt,
-    AESCannotDecrypt,
     InvalidAddress,
-    BalanceOverflow,
-    FetchDataFailed,
-    FailedToGetBlockNumber,
     RequestFailed,
-    Test,
     NoPermission,
-    CidMissingFordNftId,
+    CidMissingForNftId,
     BadTimestamp,
     NotNftOwner,
-    DownloadError,
     DecryptionError,
-    DatabaseDepositError,
-    CrossContractCallFailed,
+    /// A call into an NFT contract failed; carries the callee's own error when it returned one.
+    CrossContract(CallError),
     InvalidAlgoId,
     DatabaseError,
     NotExecuteNftOwner,
     InvalidContractAbi,
     SignatureRecoveryFailed,
-}
\ No newline at end of file
+    ProcessingLimitExceeded,
+    BufferLimitExceeded,
+    InputTooLarge,
+    OutputTooLarge,
+    TimeLimitExceeded,
+    PipelineNotFound,
+    InvalidPipeline,
+    AlgorithmVersionMismatch,
+    ScheduleNotFound,
+    ScheduleNotDue,
+    InvalidSchedule,
+    InvalidWebhookUrl,
+    WebhookNotFound,
+    WebhookNotPending,
+    WebhookRetryNotDue,
+    WebhookDeliveryFailed,
+    InvalidNonce,
+    InvalidSignature,
+    InvalidRpcResponse,
+    InvalidEndpoint,
+    InvalidConfig,
+    NoPendingOwner,
+    NotPendingOwner,
+    InvalidSignerSet,
+    NotSigner,
+    ProposalNotFound,
+    ProposalExpired,
+    AlreadyApproved,
+    MultisigRequired,
+    OperationPaused,
+    DatabaseResponseNotUtf8,
+    DatabaseResponseMalformed,
+    DatabaseSchemaMismatch,
+    InvalidResultSink,
+    ResultSinkResponseMalformed,
+    InlineSinkNotAllowed,
+    /// The request failed with this status after any retries.
+    Http { status: u16 },
+    /// Not a CIDv0, or a base32 or base36 CIDv1 with a supported codec and multihash.
+    InvalidCid,
+    /// The AS-NFT was burned and can no longer be executed.
+    AlgorithmBurned,
+    /// A signed request names an action that has no typed data definition.
+    UnknownAction,
+    /// No run of the schedule has been recorded with `record_schedule_run`.
+    ScheduleRunNotRecorded,
+    /// The recorded run of the schedule was already executed.
+    ScheduleRunAlreadyExecuted,
+}
+
+impl From<CallError> for PhalaError {
+    fn from(err: CallError) -> Self {
+        PhalaError::CrossContract(err)
+    }
+}
+
+// Errors of the shared multisig flow map to their local variants. Anything else can only come
+// from an NFT contract, so it is reported as that callee's error.
+impl From<ForestaError> for PhalaError {
+    fn from(err: ForestaError) -> Self {
+        match err {
+            ForestaError::NotAuthorized => PhalaError::NoPermission,
+            ForestaError::InvalidSignerSet => PhalaError::InvalidSignerSet,
+            ForestaError::NotSigner => PhalaError::NotSigner,
+            ForestaError::ProposalNotFound => PhalaError::ProposalNotFound,
+            ForestaError::ProposalExpired => PhalaError::ProposalExpired,
+            ForestaError::AlreadyApproved => PhalaError::AlreadyApproved,
+            ForestaError::MultisigRequired => PhalaError::MultisigRequired,
+            ForestaError::OperationPaused => PhalaError::OperationPaused,
+            err => PhalaError::CrossContract(CallError::Callee(err)),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::PhalaError;
+use alloc::{
+    string::String,
+    vec,
+    vec::Vec,
+};
+use pink_extension::chain_extension::{
+    HttpRequest,
+    HttpResponse,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Wall clock time a Phala query may run for.
+pub const QUERY_BUDGET_MS: u64 = 10_000;
+
+/// Upper bound on `HttpPolicy::max_attempts`.
+pub const MAX_HTTP_ATTEMPTS: u8 = 5;
+
+/// How outbound requests are retried.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct HttpPolicy {
+    /// Attempts made for an idempotent request, the first one included. Other requests are
+    /// only sent once.
+    pub max_attempts: u8,
+    /// Time since the first attempt after which no further attempt is started. Leaves room in
+    /// the query budget for the last response to be processed.
+    pub deadline_ms: u64,
+}
+
+impl Default for HttpPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            deadline_ms: 6_000,
+        }
+    }
+}
+
+impl HttpPolicy {
+    pub fn get(&self, url: String) -> Result<HttpResponse, PhalaError> {
+        self.send(request("GET", url, Vec::new(), Vec::new()), true)
+    }
+
+    pub fn post(
+        &self,
+        url: String,
+        body: Vec<u8>,
+        headers: Vec<(String, String)>,
+        idempotent: bool,
+    ) -> Result<HttpResponse, PhalaError> {
+        self.send(request("POST", url, body, headers), idempotent)
+    }
+
+    pub fn put(
+        &self,
+        url: String,
+        body: Vec<u8>,
+        headers: Vec<(String, String)>,
+    ) -> Result<HttpResponse, PhalaError> {
+        self.send(request("PUT", url, body, headers), true)
+    }
+
+    /// Sends `request` until it gets a 2xx response. Every attempt is cut off at the deadline.
+    /// Retries only happen for idempotent requests and for failures where another attempt can
+    /// succeed. pink has no way to sleep, so they are sent right away for as long as the deadline
+    /// leaves time; the error is the one of the last attempt.
+    pub fn send(&self, request: HttpRequest, idempotent: bool) -> Result<HttpResponse, PhalaError> {
+        let attempts = if idempotent {
+            self.max_attempts.max(1)
+        } else {
+            1
+        };
+        let started = now_in_ms();
+        let mut attempt = 1;
+        loop {
+            let error = match send_once(request.clone(), self.remaining_ms(started)) {
+                Ok(response) if (200..300).contains(&response.status_code) => return Ok(response),
+                Ok(response) => {
+                    PhalaError::Http {
+                        status: response.status_code,
+                    }
+                }
+                Err(error) => error,
+            };
+            let retryable = match error {
+                PhalaError::Http { status } => is_retryable(status),
+                _ => true,
+            };
+            if attempt >= attempts || !retryable || self.remaining_ms(started) == 0 {
+                return Err(error);
+            }
+            attempt += 1;
+        }
+    }
+
+    fn remaining_ms(&self, started: u64) -> u64 {
+        self.deadline_ms
+            .saturating_sub(now_in_ms().saturating_sub(started))
+    }
+}
+
+/// 5xx responses, timeouts and rate limiting are transient; any other 4xx will fail again.
+/// The worker reports failed connections with 5xx statuses as well.
+pub fn is_retryable(status: u16) -> bool {
+    status >= 500 || status == 408 || status == 429
+}
+
+fn request(
+    method: &str,
+    url: String,
+    body: Vec<u8>,
+    headers: Vec<(String, String)>,
+) -> HttpRequest {
+    HttpRequest {
+        url,
+        method: String::from(method),
+        headers,
+        body,
+    }
+}
+
+// A single attempt, bounded by `timeout_ms` instead of the worker's own request timeout.
+// Timeouts and failed connections surface as `RequestFailed`.
+fn send_once(request: HttpRequest, timeout_ms: u64) -> Result<HttpResponse, PhalaError> {
+    let mut responses = pink_extension::ext()
+        .batch_http_request(vec![request], timeout_ms)
+        .map_err(|_| PhalaError::RequestFailed)?;
+    match responses.pop() {
+        Some(Ok(response)) => Ok(response),
+        _ => Err(PhalaError::RequestFailed),
+    }
+}
+
+fn now_in_ms() -> u64 {
+    pink_extension::ext().untrusted_millis_since_unix_epoch()
+}
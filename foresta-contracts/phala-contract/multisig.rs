@@ -0,0 +1,50 @@
+use crate::{
+    deposit::DatabaseAuth,
+    http::HttpPolicy,
+    ownership::OwnershipBackend,
+    sandbox::ExecutionLimits,
+};
+use alloc::{
+    string::String,
+    vec::Vec,
+};
+use foresta_types::{
+    multisig::SignerSet,
+    roles::RoleType,
+    Operation,
+};
+use ink::primitives::AccountId;
+use openbrush::contracts::psp34::Id;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Critical actions on the Schrodinger contract that need approval from the signer set. The
+/// approval flow itself is `foresta_types::multisig::Multisig`.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum AdminAction {
+    SetChainId(u64),
+    SetContractId(String),
+    SetRpcApi(String),
+    SetIpfsEndpoint(String),
+    SetDatabaseEndpoint(String),
+    SetDatabaseAuth(DatabaseAuth),
+    SetAlgoNftContract(AccountId),
+    SetExecuteNftContract(AccountId),
+    SetOwnershipBackend(OwnershipBackend),
+    SetHttpPolicy(HttpPolicy),
+    RotateKey(Vec<u8>),
+    ProposeOwner(AccountId),
+    SetExecutionLimits(Id, ExecutionLimits),
+    GrantRole(RoleType, AccountId),
+    RevokeRole(RoleType, AccountId),
+    SetSignerSet(SignerSet),
+    Unpause(Operation),
+    Pause(Operation),
+    CancelOwnershipTransfer,
+}
@@ -0,0 +1,108 @@
+use crate::{
+    error::PhalaError,
+    substrate_rpc::SubstrateRpc,
+    utils::utils::get_nft_owner_address,
+};
+use alloc::string::String;
+use foresta_types::{
+    error::call_result,
+    id::id_to_u128,
+};
+use ink::{
+    env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+    },
+    primitives::AccountId,
+};
+use openbrush::contracts::psp34::Id;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Selector of `PSP34::owner_of` on an openbrush PSP34 contract.
+pub const PSP34_OWNER_OF: [u8; 4] = ink::selector_bytes!("PSP34::owner_of");
+/// Selector of `ExecutionNFT::is_owner`.
+pub const IS_OWNER: [u8; 4] = ink::selector_bytes!("is_owner");
+/// Selector of `AlgorithmNFT::get_algorithm_info`.
+pub const GET_ALGORITHM_INFO: [u8; 4] = ink::selector_bytes!("get_algorithm_info");
+
+const ZERO_EVM_ADDRESS: &str = "0000000000000000000000000000000000000000";
+
+/// Where the AS-NFT collection served by the contract lives and how its ownership is read.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum OwnershipBackend {
+    /// ERC-721 style contract on an EVM chain, queried over Ethereum JSON-RPC.
+    EvmRpc,
+    /// PSP34 contract on another ink! chain, queried through the node at `endpoint`.
+    SubstrateRpc { endpoint: String },
+    /// PSP34 contract on the same chain, queried with a cross-contract call.
+    CrossContract,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum NftOwner {
+    /// Hex encoded Ethereum address without `0x` prefix.
+    Ethereum(String),
+    Substrate(AccountId),
+}
+
+pub trait OwnershipProvider {
+    /// Returns the owner of `nft_id`, or `None` if the token does not exist.
+    fn owner_of(&self, nft_id: &Id) -> Result<Option<NftOwner>, PhalaError>;
+}
+
+pub struct EvmRpcProvider<'a> {
+    pub rpc_api: &'a str,
+    pub contract_id: &'a str,
+}
+
+impl OwnershipProvider for EvmRpcProvider<'_> {
+    fn owner_of(&self, nft_id: &Id) -> Result<Option<NftOwner>, PhalaError> {
+        // ERC-721 token ids are plain integers.
+        let nft_id = id_to_u128(nft_id).ok_or(PhalaError::InvalidAlgoId)?;
+        let owner = get_nft_owner_address(nft_id, self.contract_id.into(), self.rpc_api.into())?;
+        if owner == ZERO_EVM_ADDRESS {
+            return Ok(None);
+        }
+        Ok(Some(NftOwner::Ethereum(owner)))
+    }
+}
+
+pub struct SubstrateRpcProvider<'a> {
+    pub rpc: SubstrateRpc<'a>,
+    pub contract: AccountId,
+}
+
+impl OwnershipProvider for SubstrateRpcProvider<'_> {
+    fn owner_of(&self, nft_id: &Id) -> Result<Option<NftOwner>, PhalaError> {
+        let owner = self.rpc.psp34_owner_of(self.contract, nft_id.clone())?;
+        Ok(owner.map(NftOwner::Substrate))
+    }
+}
+
+pub struct CrossContractProvider {
+    pub contract: AccountId,
+}
+
+impl OwnershipProvider for CrossContractProvider {
+    fn owner_of(&self, nft_id: &Id) -> Result<Option<NftOwner>, PhalaError> {
+        let owner = call_result(
+            build_call::<DefaultEnvironment>()
+                .call(self.contract)
+                .exec_input(ExecutionInput::new(Selector::new(PSP34_OWNER_OF)).push_arg(nft_id))
+                .returns::<Option<AccountId>>()
+                .try_invoke(),
+        )?;
+        Ok(owner.map(NftOwner::Substrate))
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    error::PhalaError,
+    sandbox::ResourceUsage,
+};
+use alloc::{
+    string::String,
+    vec::Vec,
+};
+use openbrush::contracts::psp34::Id;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Upper bound on the number of steps a single pipeline may chain.
+pub const MAX_PIPELINE_STEPS: usize = 8;
+
+/// One stage of a pipeline: the algorithm to run, the version it is pinned to and the EA-NFT
+/// that grants access to it.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct PipelineStep {
+    pub algo_id: Id,
+    pub version: u32,
+    pub exec_id: Id,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Pipeline {
+    pub owner: ink::primitives::AccountId,
+    pub steps: Vec<PipelineStep>,
+}
+
+impl Pipeline {
+    pub fn validate(&self) -> Result<(), PhalaError> {
+        if self.steps.is_empty() || self.steps.len() > MAX_PIPELINE_STEPS {
+            return Err(PhalaError::InvalidPipeline);
+        }
+        Ok(())
+    }
+}
+
+/// Consolidated result of running every step of a pipeline in one worker call.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct PipelineReceipt {
+    pub pipeline_id: u32,
+    pub steps_executed: u32,
+    pub usage: Vec<ResourceUsage>,
+    pub output: String,
+    pub output_hash: [u8; 32],
+}
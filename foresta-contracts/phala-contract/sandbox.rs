@@ -0,0 +1,144 @@
+use crate::error::PhalaError;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Size of a buffer page in bytes, matching a WASM linear memory page.
+pub const PAGE_SIZE: u64 = 64 * 1024;
+
+/// Per-algorithm limits on the data the worker handles while serving an execution. The worker
+/// doesn't run algorithm code itself, so these bound byte counts and the time spent between
+/// steps, not instructions executed.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct ExecutionLimits {
+    /// Input and output bytes taken together.
+    pub max_bytes_processed: u64,
+    /// Pages needed to buffer the input.
+    pub max_buffer_pages: u32,
+    pub max_input_bytes: u32,
+    pub max_output_bytes: u32,
+    /// Time since the execution started, checked once each step has returned.
+    pub max_elapsed_ms: u64,
+}
+
+impl Default for ExecutionLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes_processed: 10_000_000,
+            max_buffer_pages: 256,
+            max_input_bytes: 4 * 1024 * 1024,
+            max_output_bytes: 1024 * 1024,
+            max_elapsed_ms: 10_000,
+        }
+    }
+}
+
+/// Resources consumed by a single execution, returned to the caller.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ResourceUsage {
+    pub bytes_processed: u64,
+    pub buffer_pages: u32,
+    pub input_bytes: u32,
+    pub output_bytes: u32,
+    pub elapsed_ms: u64,
+}
+
+/// Tracks resource consumption against a set of limits and fails as soon as one is exceeded.
+pub struct Meter {
+    limits: ExecutionLimits,
+    usage: ResourceUsage,
+    started_at: u64,
+}
+
+impl Meter {
+    pub fn new(limits: ExecutionLimits, started_at: u64) -> Self {
+        Self {
+            limits,
+            usage: ResourceUsage::default(),
+            started_at,
+        }
+    }
+
+    pub fn charge_bytes(&mut self, bytes: u64) -> Result<(), PhalaError> {
+        let bytes_processed = self.usage.bytes_processed.saturating_add(bytes);
+        if bytes_processed > self.limits.max_bytes_processed {
+            return Err(PhalaError::ProcessingLimitExceeded);
+        }
+        self.usage.bytes_processed = bytes_processed;
+        Ok(())
+    }
+
+    pub fn reserve_buffer(&mut self, pages: u32) -> Result<(), PhalaError> {
+        let buffer_pages = self.usage.buffer_pages.saturating_add(pages);
+        if buffer_pages > self.limits.max_buffer_pages {
+            return Err(PhalaError::BufferLimitExceeded);
+        }
+        self.usage.buffer_pages = buffer_pages;
+        Ok(())
+    }
+
+    pub fn charge_input(&mut self, bytes: usize) -> Result<(), PhalaError> {
+        let bytes = u32::try_from(bytes).map_err(|_| PhalaError::InputTooLarge)?;
+        if bytes > self.limits.max_input_bytes {
+            return Err(PhalaError::InputTooLarge);
+        }
+        self.usage.input_bytes = bytes;
+        self.reserve_buffer(pages_for(bytes))?;
+        self.charge_bytes(u64::from(bytes))
+    }
+
+    pub fn charge_output(&mut self, bytes: usize) -> Result<(), PhalaError> {
+        let bytes = u32::try_from(bytes).map_err(|_| PhalaError::OutputTooLarge)?;
+        if bytes > self.limits.max_output_bytes {
+            return Err(PhalaError::OutputTooLarge);
+        }
+        self.usage.output_bytes = bytes;
+        self.charge_bytes(u64::from(bytes))
+    }
+
+    // Called between steps; a step that overruns is only caught once it returns.
+    pub fn check_elapsed(&mut self, now: u64) -> Result<(), PhalaError> {
+        let elapsed = now.saturating_sub(self.started_at);
+        if elapsed > self.limits.max_elapsed_ms {
+            return Err(PhalaError::TimeLimitExceeded);
+        }
+        self.usage.elapsed_ms = elapsed;
+        Ok(())
+    }
+
+    pub fn finish(self) -> ResourceUsage {
+        self.usage
+    }
+}
+
+fn pages_for(bytes: u32) -> u32 {
+    let pages = u64::from(bytes).div_ceil(PAGE_SIZE);
+    pages as u32
+}
+
+/// Stage of the execution pipeline, used to report where an estimate stopped.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ExecutionStep {
+    Entitlement,
+    Download,
+    Decryption,
+    Execution,
+    Database,
+}
+
+/// Diagnostics returned by `estimate_execution`.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ExecutionEstimate {
+    pub failed_step: Option<ExecutionStep>,
+    pub error: Option<PhalaError>,
+    pub content_bytes: u32,
+    pub estimated_usage: ResourceUsage,
+}
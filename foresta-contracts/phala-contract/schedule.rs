@@ -0,0 +1,103 @@
+use crate::error::PhalaError;
+use alloc::{
+    format,
+    string::String,
+};
+use ink::primitives::AccountId;
+use openbrush::contracts::psp34::Id;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Upper bound on the number of schedule ids `due_schedules` scans per call.
+pub const MAX_SCHEDULE_PAGE: u32 = 100;
+
+/// How often a scheduled execution repeats, either in milliseconds or in blocks.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum Cadence {
+    IntervalMs(u64),
+    Blocks(u32),
+}
+
+/// A recurring execution registered by an EA-NFT holder. Credits are granted by a TREASURER once
+/// the runs are paid for; every run consumes one and the schedule pauses while none are left.
+/// A run is recorded on-chain, which consumes its credit, before `run_schedule` may execute it.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Schedule {
+    pub owner: AccountId,
+    pub algo_id: Id,
+    pub exec_id: Id,
+    /// Canonical form of the input CID, parsed at registration.
+    pub input_cid: String,
+    pub cadence: Cadence,
+    pub next_run: u64,
+    pub credits: u32,
+    /// Slot, in milliseconds or blocks like `next_run`, of the last recorded run.
+    pub recorded_run: Option<u64>,
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<(), PhalaError> {
+        let invalid_cadence = match self.cadence {
+            Cadence::IntervalMs(interval) => interval == 0,
+            Cadence::Blocks(blocks) => blocks == 0,
+        };
+        if invalid_cadence {
+            return Err(PhalaError::InvalidSchedule);
+        }
+        Ok(())
+    }
+
+    // A new schedule is due immediately.
+    pub fn start(&mut self, now: u64, block: u32) {
+        self.next_run = self.current(now, block);
+    }
+
+    pub fn is_due(&self, now: u64, block: u32) -> bool {
+        self.credits > 0 && self.current(now, block) >= self.next_run
+    }
+
+    pub fn grant_credits(&mut self, credits: u32) -> Result<(), PhalaError> {
+        if credits == 0 {
+            return Err(PhalaError::InvalidSchedule);
+        }
+        self.credits = self.credits.saturating_add(credits);
+        Ok(())
+    }
+
+    // Consumes one credit, records the due run and moves the next run one cadence past the
+    // current time.
+    pub fn record_run(&mut self, now: u64, block: u32) -> Result<(), PhalaError> {
+        if !self.is_due(now, block) {
+            return Err(PhalaError::ScheduleNotDue);
+        }
+        self.credits -= 1;
+        self.recorded_run = Some(self.next_run);
+        self.next_run = match self.cadence {
+            Cadence::IntervalMs(interval) => now.saturating_add(interval),
+            Cadence::Blocks(blocks) => u64::from(block).saturating_add(u64::from(blocks)),
+        };
+        Ok(())
+    }
+
+    fn current(&self, now: u64, block: u32) -> u64 {
+        match self.cadence {
+            Cadence::IntervalMs(_) => now,
+            Cadence::Blocks(_) => u64::from(block),
+        }
+    }
+}
+
+/// Cache entry marking the recorded run at `slot` as executed.
+pub fn run_cache_key(schedule_id: u32, slot: u64) -> String {
+    format!("schedule-run:{}:{}", schedule_id, slot)
+}
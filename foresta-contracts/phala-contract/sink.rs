@@ -0,0 +1,380 @@
+use crate::{
+    config::is_valid_endpoint,
+    deposit::{
+        hmac_sha256,
+        post_deposit,
+        sha256,
+        DatabaseAuth,
+        DepositRequest,
+    },
+    error::PhalaError,
+    http::HttpPolicy,
+};
+use alloc::{
+    format,
+    string::{
+        String,
+        ToString,
+    },
+    vec,
+    vec::Vec,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+use serde::Deserialize;
+
+/// Path of the IPFS HTTP API call that adds and pins a file.
+pub const IPFS_ADD_PATH: &str = "/api/v0/add?pin=true";
+
+/// Where the documents produced by deposits and executions are delivered.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum ResultSink {
+    /// The contract wide `database_endpoint`, authenticated with `database_auth`.
+    #[default]
+    Database,
+    /// Any other REST endpoint speaking the deposit protocol. Requests to it are not
+    /// authenticated, so the contract credentials never leave for a third party.
+    Rest { endpoint: String },
+    /// S3 compatible object storage, written with a SigV4 signed PUT.
+    S3(S3Sink),
+    /// An IPFS node; the document is added and pinned through its HTTP API.
+    Ipfs { endpoint: String },
+    /// Nothing is stored and the content is handed back to the caller.
+    Inline,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct S3Sink {
+    /// Service endpoint, e.g. `https://s3.eu-west-1.amazonaws.com`. Objects are addressed
+    /// path style below it.
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+/// Contract settings the sinks deliver with.
+pub struct DeliveryContext<'a> {
+    pub database_endpoint: &'a str,
+    pub database_auth: &'a DatabaseAuth,
+    pub http_policy: &'a HttpPolicy,
+    /// Dates S3 signatures.
+    pub now_ms: u64,
+}
+
+/// Where a sink put the delivered document.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum SinkReceipt {
+    Deposited { deposit_id: String },
+    Stored { url: String },
+    Pinned { cid: String },
+    Inline { content: String },
+}
+
+impl SinkReceipt {
+    /// Deposit id, object URL, CID or the inline content, whichever the sink produced.
+    pub fn into_reference(self) -> String {
+        match self {
+            SinkReceipt::Deposited { deposit_id } => deposit_id,
+            SinkReceipt::Stored { url } => url,
+            SinkReceipt::Pinned { cid } => cid,
+            SinkReceipt::Inline { content } => content,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct IpfsAddResponse {
+    #[serde(rename = "Hash")]
+    hash: String,
+}
+
+impl ResultSink {
+    pub fn validate(&self) -> Result<(), PhalaError> {
+        let valid = match self {
+            ResultSink::Database | ResultSink::Inline => true,
+            ResultSink::Rest { endpoint } | ResultSink::Ipfs { endpoint } => {
+                is_valid_endpoint(endpoint)
+            }
+            ResultSink::S3(s3) => s3.is_valid(),
+        };
+        if !valid {
+            return Err(PhalaError::InvalidResultSink);
+        }
+        Ok(())
+    }
+
+    /// Copy that is safe to return from queries: the S3 secret is blanked out.
+    pub fn redacted(&self) -> ResultSink {
+        match self {
+            ResultSink::S3(s3) => {
+                ResultSink::S3(S3Sink {
+                    secret_access_key: String::new(),
+                    ..s3.clone()
+                })
+            }
+            sink => sink.clone(),
+        }
+    }
+
+    /// Delivers `request`. The contract database endpoint and credentials are only used by
+    /// `ResultSink::Database`.
+    pub fn deliver(
+        &self,
+        request: &DepositRequest,
+        context: &DeliveryContext,
+    ) -> Result<SinkReceipt, PhalaError> {
+        match self {
+            ResultSink::Database => {
+                let receipt = post_deposit(
+                    context.database_endpoint,
+                    context.database_auth,
+                    context.http_policy,
+                    request,
+                )?;
+                Ok(SinkReceipt::Deposited {
+                    deposit_id: receipt.deposit_id,
+                })
+            }
+            ResultSink::Rest { endpoint } => {
+                let receipt =
+                    post_deposit(endpoint, &DatabaseAuth::None, context.http_policy, request)?;
+                Ok(SinkReceipt::Deposited {
+                    deposit_id: receipt.deposit_id,
+                })
+            }
+            ResultSink::S3(s3) => s3.put_object(request, context),
+            ResultSink::Ipfs { endpoint } => ipfs_add(endpoint, request, context.http_policy),
+            ResultSink::Inline => {
+                Ok(SinkReceipt::Inline {
+                    content: request.content.to_string(),
+                })
+            }
+        }
+    }
+}
+
+impl S3Sink {
+    fn is_valid(&self) -> bool {
+        is_valid_endpoint(&self.endpoint)
+            && is_valid_bucket_name(&self.bucket)
+            && !self.region.is_empty()
+            && self
+                .region
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+            && !self.access_key_id.is_empty()
+            && self
+                .access_key_id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric())
+            && !self.secret_access_key.is_empty()
+            && self.secret_access_key.bytes().all(|b| b.is_ascii_graphic())
+    }
+
+    // Objects are keyed by content, so a retried PUT overwrites the same object.
+    fn put_object(
+        &self,
+        request: &DepositRequest,
+        context: &DeliveryContext,
+    ) -> Result<SinkReceipt, PhalaError> {
+        let body = request.to_json()?;
+        let payload_hash = hex::encode(sha256(&body));
+        let amz_date = amz_date(context.now_ms);
+        let (host, base_path) = split_endpoint(&self.endpoint);
+        let path = format!("{}/{}/{}", base_path, self.bucket, object_key(request));
+
+        let mut headers = vec![
+            ("host".to_string(), host.to_string()),
+            ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+            ("x-amz-date".to_string(), amz_date.clone()),
+        ];
+        let authorization = self.authorization("PUT", &path, &headers, &payload_hash, &amz_date);
+        headers.push(("Authorization".to_string(), authorization));
+        headers.push(("Content-Type".to_string(), "application/json".to_string()));
+
+        let url = format!(
+            "{}{}",
+            self.endpoint.trim_end_matches('/'),
+            canonical_uri(&path[base_path.len()..])
+        );
+        context.http_policy.put(url.clone(), body, headers)?;
+        Ok(SinkReceipt::Stored { url })
+    }
+
+    /// `Authorization` header value for an AWS Signature Version 4 request without a query
+    /// string. `path` is signed in its `canonical_uri` form; every header in `headers` is signed.
+    pub fn authorization(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &[(String, String)],
+        payload_hash: &str,
+        amz_date: &str,
+    ) -> String {
+        let mut headers: Vec<(String, &str)> = headers
+            .iter()
+            .map(|(name, value)| (name.to_ascii_lowercase(), value.trim()))
+            .collect();
+        headers.sort();
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "{}\n{}\n\n{}\n{}\n{}",
+            method,
+            canonical_uri(path),
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+
+        let date = &amz_date[..8];
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(sha256(canonical_request.as_bytes()))
+        );
+
+        let mut key = hmac_sha256(
+            format!("AWS4{}", self.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        );
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            key = hmac_sha256(&key, part.as_bytes());
+        }
+        let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{},SignedHeaders={},Signature={}",
+            self.access_key_id, scope, signed_headers, signature
+        )
+    }
+}
+
+// The multipart boundary is derived from the body so the body can't contain it. Adding the
+// same content twice yields the same CID, so the request is retried.
+fn ipfs_add(
+    endpoint: &str,
+    request: &DepositRequest,
+    http_policy: &HttpPolicy,
+) -> Result<SinkReceipt, PhalaError> {
+    let document = request.to_json()?;
+    let boundary = format!("foresta-{}", hex::encode(&sha256(&document)[..16]));
+
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"result.json\"\r\n\
+         Content-Type: application/json\r\n\r\n",
+        boundary
+    )
+    .into_bytes();
+    body.extend_from_slice(&document);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+    let headers = vec![(
+        "Content-Type".to_string(),
+        format!("multipart/form-data; boundary={}", boundary),
+    )];
+    let url = format!("{}{}", endpoint.trim_end_matches('/'), IPFS_ADD_PATH);
+    let response = http_policy.post(url, body, headers, true)?;
+    let added: IpfsAddResponse = serde_json::from_slice(&response.body)
+        .map_err(|_| PhalaError::ResultSinkResponseMalformed)?;
+    Ok(SinkReceipt::Pinned { cid: added.hash })
+}
+
+/// `<algo_id>/<exec_id or "deposit">/<payload hash>.json`
+pub fn object_key(request: &DepositRequest) -> String {
+    format!(
+        "{}/{}/{}.json",
+        request.algo_id,
+        request.exec_id.as_deref().unwrap_or("deposit"),
+        request.payload_hash.trim_start_matches("0x")
+    )
+}
+
+/// Percent-encodes every segment of `path` except RFC 3986 unreserved characters, as SigV4 and
+/// S3 expect. Slashes between segments are kept.
+pub fn canonical_uri(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~' | b'/') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// `YYYYMMDDTHHMMSSZ` in UTC, as SigV4 expects in `x-amz-date`.
+pub fn amz_date(timestamp_ms: u64) -> String {
+    let secs = timestamp_ms / 1000;
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    let secs_of_day = secs % 86_400;
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
+
+// Days since 1970-01-01 to a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+// Splits `https://host/prefix/` into `host` and `/prefix`.
+fn split_endpoint(endpoint: &str) -> (&str, &str) {
+    let rest = endpoint
+        .strip_prefix("https://")
+        .or_else(|| endpoint.strip_prefix("http://"))
+        .unwrap_or(endpoint)
+        .trim_end_matches('/');
+    match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, ""),
+    }
+}
+
+fn is_valid_bucket_name(bucket: &str) -> bool {
+    let bytes = bucket.as_bytes();
+    (3..=63).contains(&bytes.len())
+        && bytes
+            .iter()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || *b == b'.' || *b == b'-')
+        && bytes[0].is_ascii_alphanumeric()
+        && bytes[bytes.len() - 1].is_ascii_alphanumeric()
+}
@@ -0,0 +1,181 @@
+use crate::{
+    error::PhalaError,
+    http::HttpPolicy,
+    ownership::{
+        GET_ALGORITHM_INFO,
+        IS_OWNER,
+        PSP34_OWNER_OF,
+    },
+};
+use alloc::{
+    format,
+    string::String,
+    vec::Vec,
+};
+use foresta_types::{
+    AlgorithmInfo,
+    CallError,
+    ForestaError,
+};
+use ink::primitives::AccountId;
+use openbrush::contracts::psp34::Id;
+use scale::{
+    Decode,
+    Encode,
+};
+use serde::Deserialize;
+
+/// Runtime API used to dry-run a contract message through `state_call`.
+const CONTRACTS_API_CALL: &str = "ContractsApi_call";
+
+/// Return flag set by a contract that reverted.
+const FLAG_REVERT: u32 = 1;
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
+pub struct Weight {
+    #[codec(compact)]
+    pub ref_time: u64,
+    #[codec(compact)]
+    pub proof_size: u64,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub enum StorageDeposit {
+    Refund(u128),
+    Charge(u128),
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct ExecReturnValue {
+    pub flags: u32,
+    pub data: Vec<u8>,
+}
+
+/// Leading fields of `pallet_contracts::ContractExecResult`. The `result` that follows is decoded
+/// separately because its error side is a runtime specific `DispatchError`.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct ContractExecResultHeader {
+    pub gas_consumed: Weight,
+    pub gas_required: Weight,
+    pub storage_deposit: StorageDeposit,
+    pub debug_message: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<String>,
+}
+
+/// Read-only contract calls against a Substrate node over JSON-RPC. Used when the NFT contracts
+/// live on a parachain that the worker can't reach with cross-contract calls.
+pub struct SubstrateRpc<'a> {
+    pub endpoint: &'a str,
+    pub origin: AccountId,
+    pub http_policy: HttpPolicy,
+}
+
+impl SubstrateRpc<'_> {
+    /// `PSP34::owner_of` on an AS-NFT or EA-NFT contract.
+    pub fn psp34_owner_of(
+        &self,
+        contract: AccountId,
+        id: Id,
+    ) -> Result<Option<AccountId>, PhalaError> {
+        self.call_message(contract, message_input(PSP34_OWNER_OF, id))
+    }
+
+    /// `ExecutionNFT::is_owner` for an EA-NFT.
+    pub fn is_owner(
+        &self,
+        contract: AccountId,
+        exec_id: Id,
+        owner: AccountId,
+    ) -> Result<bool, PhalaError> {
+        self.call_message(contract, message_input(IS_OWNER, (exec_id, owner)))
+    }
+
+    /// `AlgorithmNFT::get_algorithm_info` for an AS-NFT.
+    pub fn algorithm_info(
+        &self,
+        contract: AccountId,
+        algo_id: Id,
+    ) -> Result<AlgorithmInfo, PhalaError> {
+        let info: Result<AlgorithmInfo, ForestaError> =
+            self.call_message(contract, message_input(GET_ALGORITHM_INFO, algo_id))?;
+        info.map_err(|err| CallError::Callee(err).into())
+    }
+
+    /// Dry-runs the message `input` on `contract` and returns the data it returned.
+    pub fn contract_call(
+        &self,
+        contract: AccountId,
+        input: Vec<u8>,
+    ) -> Result<Vec<u8>, PhalaError> {
+        let args = (
+            self.origin,
+            contract,
+            0u128,
+            Option::<Weight>::None,
+            Option::<u128>::None,
+            input,
+        );
+        let result = self.state_call(CONTRACTS_API_CALL, &args.encode())?;
+        decode_contract_result(&result)
+    }
+
+    /// Calls an ink! message and decodes its `MessageResult<T>`.
+    pub fn call_message<T: Decode>(
+        &self,
+        contract: AccountId,
+        input: Vec<u8>,
+    ) -> Result<T, PhalaError> {
+        let data = self.contract_call(contract, input)?;
+        let result: Result<T, ink::LangError> =
+            Decode::decode(&mut data.as_slice()).map_err(|_| PhalaError::InvalidRpcResponse)?;
+        result.map_err(|err| CallError::from(err).into())
+    }
+
+    fn state_call(&self, method: &str, data: &[u8]) -> Result<Vec<u8>, PhalaError> {
+        let body = format!(
+            "{{\"id\":1,\"jsonrpc\":\"2.0\",\"method\":\"state_call\",\"params\":[\"{}\",\"0x{}\"]}}",
+            method,
+            hex::encode(data)
+        );
+        let headers = alloc::vec![("Content-Type".into(), "application/json".into())];
+        // `state_call` only reads state, so it is safe to retry.
+        let response = self.http_policy.post(
+            String::from(self.endpoint),
+            body.into_bytes(),
+            headers,
+            true,
+        )?;
+
+        let response: RpcResponse =
+            serde_json::from_slice(&response.body).map_err(|_| PhalaError::InvalidRpcResponse)?;
+        let result = response.result.ok_or(PhalaError::RequestFailed)?;
+        hex::decode(result.trim_start_matches("0x")).map_err(|_| PhalaError::InvalidRpcResponse)
+    }
+}
+
+fn message_input<Args: Encode>(selector: [u8; 4], args: Args) -> Vec<u8> {
+    let mut input = selector.to_vec();
+    args.encode_to(&mut input);
+    input
+}
+
+pub fn decode_contract_result(mut encoded: &[u8]) -> Result<Vec<u8>, PhalaError> {
+    let input = &mut encoded;
+    ContractExecResultHeader::decode(input).map_err(|_| PhalaError::InvalidRpcResponse)?;
+    let is_ok = u8::decode(input).map_err(|_| PhalaError::InvalidRpcResponse)? == 0;
+    // The dispatch error itself isn't decoded; the node reports it for missing contracts and
+    // traps alike.
+    if !is_ok {
+        return Err(CallError::Environment.into());
+    }
+
+    let value = ExecReturnValue::decode(input).map_err(|_| PhalaError::InvalidRpcResponse)?;
+    if value.flags & FLAG_REVERT != 0 {
+        return Err(CallError::Reverted.into());
+    }
+    Ok(value.data)
+}
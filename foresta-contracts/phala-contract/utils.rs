@@ -1,8 +1,12 @@
 use crate::alloc::string::ToString;
+use crate::error::PhalaError;
 use alloc::vec::Vec;
+use alloc::format;
 use alloc::string::String;
 
+use ink::primitives::AccountId;
 use ink_env::{ecdsa_recover, ecdsa_to_eth_address};
+use pink_extension::chain_extension::signing::{verify, SigType};
 
 use pink_web3::{
     types::{U256, Address},
@@ -34,58 +38,262 @@ pub mod utils {
         hash
     }
 
+    /*
+    Builds the text a client signs for a request. Binding the action, contract, NFT, payload and
+    nonce stops a signature from being replayed for anything else.
+     */
+    pub fn request_message(
+        action: &str,
+        contract: &[u8],
+        nft_id: u8,
+        payload: &str,
+        nonce: u64,
+        timestamp: u64,
+    ) -> String {
+        format!(
+            "APILLON_REQUEST_MSG: action={};contract=0x{};nft_id={};payload={};nonce={};timestamp={}",
+            action,
+            hex::encode(contract),
+            nft_id,
+            payload,
+            nonce,
+            timestamp
+        )
+    }
+
     fn to_utf8_bytes(s: &str) -> Vec<u8> {
         s.as_bytes().to_vec()
     }
 
-    fn hash_keccak_256(input: &[u8]) -> [u8; 32] {
+    pub fn hash_keccak_256(input: &[u8]) -> [u8; 32] {
         let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
         ink_env::hash_bytes::<hash::Keccak256>(input, &mut output);
         output
     }
 
-    pub fn recover_acc_address(signature: String, message: [u8; 32]) -> String {
+    pub fn recover_acc_address(signature: String, message: [u8; 32]) -> Result<String, PhalaError> {
         let signature: [u8; 65] = hex::decode(signature)
-            .unwrap()
+            .map_err(|_| PhalaError::SignatureRecoveryFailed)?
             .as_slice()
             .try_into()
-            .unwrap();
-
-        let message_hash = message.as_slice().try_into().unwrap();
+            .map_err(|_| PhalaError::SignatureRecoveryFailed)?;
+        // Recovery id, either raw or with Ethereum's offset of 27.
+        if !matches!(signature[64], 0 | 1 | 27 | 28) {
+            return Err(PhalaError::SignatureRecoveryFailed);
+        }
 
         let mut pub_key = [0u8; 33];
         let mut address = [0u8; 20];
-        
-        ecdsa_recover(&signature, &message_hash, &mut pub_key).unwrap();
-        ecdsa_to_eth_address(&mut pub_key, &mut address).unwrap();
 
-        hex::encode(address)
+        ecdsa_recover(&signature, &message, &mut pub_key)
+            .map_err(|_| PhalaError::SignatureRecoveryFailed)?;
+        ecdsa_to_eth_address(&pub_key, &mut address)
+            .map_err(|_| PhalaError::SignatureRecoveryFailed)?;
+
+        Ok(hex::encode(address))
     }
 
-    // pub fn get_nft_owner_address(nft_id: u8, contract_id: String, rpc_api: String) -> String {
-    //     "0xabcd1234".to_string() // Mock address for testing
-    // }
+    /*
+    The part of the ERC-721 ABI the owner lookup needs, so it doesn't depend on build artifacts.
+     */
+    const ERC721_OWNER_OF_ABI: &[u8] = br#"[{"type":"function","name":"ownerOf","stateMutability":"view","inputs":[{"name":"tokenId","type":"uint256"}],"outputs":[{"name":"","type":"address"}]}]"#;
 
-    pub fn get_nft_owner_address(nft_id: u8, contract_id: String, rpc_api: String) -> String  {
-        let default: Address =  Address::zero();
+    pub fn get_nft_owner_address(
+        nft_id: u128,
+        contract_id: String,
+        rpc_api: String,
+    ) -> Result<String, PhalaError> {
         let phttp = PinkHttp::new(rpc_api);
         let eth = Eth::new(phttp);
-        let addr = String::from(contract_id).parse().unwrap();
+        let addr: Address = contract_id.parse().map_err(|_| PhalaError::InvalidAddress)?;
 
-        let contract = Contract::from_json(eth, addr, include_bytes!("../../target/ink/algo_nft/algo_nft.json")).unwrap();
+        let contract = Contract::from_json(eth, addr, ERC721_OWNER_OF_ABI)
+            .map_err(|_| PhalaError::InvalidContractAbi)?;
 
         let query = "ownerOf";
-        let address: Address = resolve_ready(contract.query(&query, (U256::from(nft_id), ), None, Options::default(), None)).unwrap_or(default);
-        hex::encode(address.0)
+        let address: Address = resolve_ready(contract.query(&query, (U256::from(nft_id), ), None, Options::default(), None))
+            .map_err(|_| PhalaError::RequestFailed)?;
+        Ok(hex::encode(address.0))
     }
 
     /*
     Checks if message signer is owner of NFT with specified id.
      */
-    pub fn is_nft_owner(signature: String, message: [u8; 32], nft_id: u8, contract_id: String, rpc_api: String) -> bool{
-        let signer_address = recover_acc_address(signature, message);
-        let nft_owner_address = get_nft_owner_address(nft_id, contract_id, rpc_api);
+    pub fn is_nft_owner(signature: String, message: [u8; 32], nft_id: u8, contract_id: String, rpc_api: String) -> Result<bool, PhalaError> {
+        let signer_address = recover_acc_address(signature, message)?;
+        let nft_owner_address = get_nft_owner_address(nft_id.into(), contract_id, rpc_api)?;
+
+        Ok(signer_address == nft_owner_address)
+    }
+
+    /*
+    EIP-712 typed data. Wallets show these requests field by field, and the domain (chain id and
+    the contract account as salt) stops a signature from being reused on another chain or
+    deployment.
+     */
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum SignatureFormat {
+        PersonalSign,
+        TypedData,
+    }
+
+    /*
+    How a signed request is authorized. `Ecdsa` is the Ethereum path, checked against the EVM-side
+    NFT owner. `Sr25519` and `Ed25519` carry the Substrate account that signed and are checked
+    against the PSP34 owner on the ink! chain.
+     */
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum AuthorizationScheme {
+        Ecdsa(SignatureFormat),
+        Sr25519(AccountId),
+        Ed25519(AccountId),
+    }
+
+    /*
+    Verifies a Substrate signature over `message` made by `account`. Wallets such as the
+    polkadot.js extension wrap raw payloads in `<Bytes>` tags, so both forms are accepted.
+     */
+    pub fn verify_substrate_signature(
+        scheme: &AuthorizationScheme,
+        message: &str,
+        signature: &str,
+    ) -> bool {
+        let (account, sig_type) = match scheme {
+            AuthorizationScheme::Sr25519(account) => (account, SigType::Sr25519),
+            AuthorizationScheme::Ed25519(account) => (account, SigType::Ed25519),
+            AuthorizationScheme::Ecdsa(_) => return false,
+        };
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        let public_key: &[u8; 32] = account.as_ref();
+        let wrapped = format!("<Bytes>{}</Bytes>", message);
+
+        verify(wrapped.as_bytes(), public_key, &signature, sig_type)
+            || verify(message.as_bytes(), public_key, &signature, sig_type)
+    }
+
+    const EIP712_DOMAIN_TYPE: &str =
+        "EIP712Domain(string name,string version,uint256 chainId,bytes32 salt)";
+    pub const EIP712_DOMAIN_NAME: &str = "Foresta";
+    pub const EIP712_DOMAIN_VERSION: &str = "1";
+
+    pub struct Eip712Domain {
+        pub chain_id: u64,
+        pub salt: [u8; 32],
+    }
+
+    impl Eip712Domain {
+        pub fn separator(&self) -> [u8; 32] {
+            let mut encoded = Vec::new();
+            encoded.extend_from_slice(&hash_keccak_256(EIP712_DOMAIN_TYPE.as_bytes()));
+            encoded.extend_from_slice(&hash_keccak_256(EIP712_DOMAIN_NAME.as_bytes()));
+            encoded.extend_from_slice(&hash_keccak_256(EIP712_DOMAIN_VERSION.as_bytes()));
+            encoded.extend_from_slice(&encode_uint(self.chain_id.into()));
+            encoded.extend_from_slice(&self.salt);
+            hash_keccak_256(&encoded)
+        }
+    }
+
+    pub trait TypedData {
+        const TYPE: &'static str;
+
+        fn encode_data(&self) -> Vec<u8>;
+
+        fn struct_hash(&self) -> [u8; 32] {
+            let mut encoded = Vec::new();
+            encoded.extend_from_slice(&hash_keccak_256(Self::TYPE.as_bytes()));
+            encoded.extend_from_slice(&self.encode_data());
+            hash_keccak_256(&encoded)
+        }
+    }
+
+    pub struct SetCid<'a> {
+        pub nft_id: u8,
+        pub cid: &'a str,
+        pub nonce: u64,
+        pub timestamp: u64,
+    }
+
+    impl TypedData for SetCid<'_> {
+        const TYPE: &'static str =
+            "SetCid(uint256 nftId,string cid,uint256 nonce,uint256 timestamp)";
+
+        fn encode_data(&self) -> Vec<u8> {
+            encode_request_fields(self.nft_id, self.cid, self.nonce, self.timestamp)
+        }
+    }
+
+    pub struct DepositContent<'a> {
+        pub nft_id: u8,
+        pub cid: &'a str,
+        pub nonce: u64,
+        pub timestamp: u64,
+    }
+
+    impl TypedData for DepositContent<'_> {
+        const TYPE: &'static str =
+            "DepositContent(uint256 nftId,string cid,uint256 nonce,uint256 timestamp)";
+
+        fn encode_data(&self) -> Vec<u8> {
+            encode_request_fields(self.nft_id, self.cid, self.nonce, self.timestamp)
+        }
+    }
+
+    pub struct Execute {
+        pub algo_id: u128,
+        pub exec_id: u128,
+        pub nonce: u64,
+        pub timestamp: u64,
+    }
+
+    impl TypedData for Execute {
+        const TYPE: &'static str =
+            "Execute(uint256 algoId,uint256 execId,uint256 nonce,uint256 timestamp)";
+
+        fn encode_data(&self) -> Vec<u8> {
+            let mut encoded = Vec::new();
+            encoded.extend_from_slice(&encode_uint(self.algo_id));
+            encoded.extend_from_slice(&encode_uint(self.exec_id));
+            encoded.extend_from_slice(&encode_uint(self.nonce.into()));
+            encoded.extend_from_slice(&encode_uint(self.timestamp.into()));
+            encoded
+        }
+    }
+
+    /*
+    Digest a wallet signs for `eth_signTypedData_v4`.
+     */
+    pub fn hash_typed_data<T: TypedData>(domain: &Eip712Domain, data: &T) -> [u8; 32] {
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(b"\x19\x01");
+        encoded.extend_from_slice(&domain.separator());
+        encoded.extend_from_slice(&data.struct_hash());
+        hash_keccak_256(&encoded)
+    }
+
+    pub fn recover_typed_data_signer<T: TypedData>(
+        signature: String,
+        domain: &Eip712Domain,
+        data: &T,
+    ) -> Result<String, PhalaError> {
+        recover_acc_address(signature, hash_typed_data(domain, data))
+    }
+
+    fn encode_request_fields(nft_id: u8, cid: &str, nonce: u64, timestamp: u64) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(&encode_uint(nft_id.into()));
+        encoded.extend_from_slice(&hash_keccak_256(cid.as_bytes()));
+        encoded.extend_from_slice(&encode_uint(nonce.into()));
+        encoded.extend_from_slice(&encode_uint(timestamp.into()));
+        encoded
+    }
 
-        signer_address == nft_owner_address
+    fn encode_uint(value: u128) -> [u8; 32] {
+        let mut encoded = [0u8; 32];
+        encoded[16..].copy_from_slice(&value.to_be_bytes());
+        encoded
     }
-}
\ No newline at end of file
+}
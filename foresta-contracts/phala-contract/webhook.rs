@@ -0,0 +1,107 @@
+use crate::{
+    config::is_valid_endpoint,
+    error::PhalaError,
+};
+use alloc::{
+    format,
+    string::String,
+    vec::Vec,
+};
+use foresta_types::id::id_to_string;
+use openbrush::contracts::psp34::Id;
+use scale::{
+    Decode,
+    Encode,
+};
+use serde::Serialize;
+
+/// Salt for the sr25519 key that signs webhook notifications.
+pub const WEBHOOK_KEY_SALT: &[u8] = b"foresta-webhook";
+
+/// Header carrying the hex encoded sr25519 signature of the notification body.
+pub const SIGNATURE_HEADER: &str = "X-Foresta-Signature";
+
+/// Number of delivery attempts before a notification is dropped.
+pub const MAX_WEBHOOK_ATTEMPTS: u8 = 5;
+
+/// Delay before the first retry; doubled on every further attempt.
+pub const WEBHOOK_BASE_BACKOFF_MS: u64 = 30 * 1000;
+
+#[derive(Encode, Decode, Serialize, Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionStatus {
+    Succeeded,
+    Failed,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct WebhookNotification {
+    pub exec_id: Id,
+    pub algo_id: Id,
+    pub status: ExecutionStatus,
+    pub output_hash: [u8; 32],
+    /// Position among the notifications for `exec_id`, so receivers can drop redeliveries.
+    pub sequence: u64,
+}
+
+/// JSON body posted to the webhook.
+#[derive(Serialize)]
+struct WebhookPayload {
+    exec_id: String,
+    algo_id: String,
+    status: ExecutionStatus,
+    output_hash: String,
+    sequence: u64,
+}
+
+impl WebhookNotification {
+    pub fn to_json(&self) -> Result<Vec<u8>, PhalaError> {
+        let payload = WebhookPayload {
+            exec_id: id_to_string(&self.exec_id),
+            algo_id: id_to_string(&self.algo_id),
+            status: self.status,
+            output_hash: format!("0x{}", hex::encode(self.output_hash)),
+            sequence: self.sequence,
+        };
+        serde_json::to_vec(&payload).map_err(|_| PhalaError::WebhookDeliveryFailed)
+    }
+}
+
+/// A notification whose delivery failed and is waiting for its next attempt.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct PendingWebhook {
+    pub notification: WebhookNotification,
+    pub attempts: u8,
+    pub next_attempt_at: u64,
+}
+
+impl PendingWebhook {
+    /// Records a failed attempt made at `now`. Returns `false` once the attempts are used up.
+    pub fn schedule_retry(&mut self, now: u64) -> bool {
+        self.attempts = self.attempts.saturating_add(1);
+        if self.attempts >= MAX_WEBHOOK_ATTEMPTS {
+            return false;
+        }
+        let backoff = WEBHOOK_BASE_BACKOFF_MS.saturating_mul(1 << (self.attempts - 1));
+        self.next_attempt_at = now.saturating_add(backoff);
+        true
+    }
+}
+
+pub fn is_valid_callback_url(url: &str) -> bool {
+    is_valid_endpoint(url)
+}
+
+/// Cache entry of a pending redelivery. Every notification has its own, so a newer failure
+/// doesn't overwrite an older one still waiting.
+pub fn cache_key(exec_id: &Id, sequence: u64) -> String {
+    format!("webhook:{}:{}", id_to_string(exec_id), sequence)
+}
+
+/// Cache entry holding the sequence number of the next notification for `exec_id`.
+pub fn sequence_cache_key(exec_id: &Id) -> String {
+    format!("webhook-sequence:{}", id_to_string(exec_id))
+}